@@ -0,0 +1,683 @@
+type Result<T> = std::result::Result<T, SpiralError<'static>>;
+type Span = super::span::Span;
+type SpiralError<'a> = super::spiral_error::SpiralError<'a>;
+type Token = super::tokenizer::Token;
+type TokenType = super::tokenizer::TokenType;
+
+pub struct Layout<'a> {
+    pub source: &'a str,
+    pub tokens: Vec<Token>,
+}
+
+#[derive(PartialEq)]
+enum ContextKind {
+    TopLevel,
+    Let,
+    Branch,
+    Bracket,
+}
+
+struct Context {
+    kind: ContextKind,
+    column: usize,
+    indentation: usize,
+}
+
+// Replaces newlines with explicit block tokens using the offside rule:
+//
+// - top-level declarations are separated wherever a line starts in the
+//   column of the first declaration,
+// - `let` opens a block of bindings in the column of the token that follows
+//   it, closed by `in` or by a line starting to the left of that column,
+// - a line indented past an `if`, `when` or `else` line opens a branch block
+//   that lasts until a line starts to the left of it,
+// - a closing bracket closes every block opened since its opening bracket.
+//
+// Any other indented line continues the line above it. Doc comments are
+// passed through like whitespace, so they never open or separate a block,
+// but must start their line so that it is clear what they document.
+impl<'a> Layout<'a> {
+    pub fn build(source: &'a str, tokens: Vec<Token>) -> Layout<'a> {
+        Layout { source, tokens }
+    }
+
+    pub fn execute(&mut self) -> Result<Vec<Token>> {
+        let mut output = Vec::new();
+        let mut contexts: Vec<Context> = Vec::new();
+        // Columns count characters, and are advanced from the last token
+        // rather than recounted from the start of the line. Multi-line strings
+        // and comments restart the count at their last line break.
+        let mut column = 0;
+        let mut column_offset = 0;
+        let mut line_indentation = 0;
+        let mut at_line_start = true;
+        let mut pending_let: Option<usize> = None;
+        let mut pending_branch: Option<usize> = None;
+
+        for token in self.tokens.iter() {
+            match token.token_type {
+                TokenType::Newline => {
+                    column = 0;
+                    column_offset = token.span.end as usize;
+                    at_line_start = true;
+                    continue;
+                }
+                TokenType::DocComment if !at_line_start => {
+                    return Err(SpiralError {
+                        error_text: "Doc comments must be on a line of their own",
+                        help_text: "Move the doc comment above the declaration it documents, or use -- for an ordinary comment",
+                        span: token.span,
+                    });
+                }
+                TokenType::Whitespace | TokenType::DocComment => {
+                    output.push(token.clone());
+                    continue;
+                }
+                _ => {}
+            }
+
+            let start = token.span.start as usize;
+            let gap = &self.source[column_offset..start];
+            match gap.rfind('\n') {
+                Some(index) => column = gap[index + 1..].chars().count(),
+                None => column += gap.chars().count(),
+            }
+            column_offset = start;
+            if at_line_start {
+                line_indentation = column;
+            }
+            let mut closed_let = false;
+
+            if let Some(indentation) = pending_let.take() {
+                if at_line_start && column <= indentation {
+                    return Err(Self::indentation_error(
+                        token,
+                        "Let bindings must be indented further than the 'let' line",
+                    ));
+                }
+                contexts.push(Context {
+                    kind: ContextKind::Let,
+                    column,
+                    indentation,
+                });
+                output.push(Self::layout_token(TokenType::BlockOpen, token));
+            } else if contexts.is_empty() {
+                contexts.push(Context {
+                    kind: ContextKind::TopLevel,
+                    column,
+                    indentation: column,
+                });
+            } else if at_line_start {
+                Self::close_brackets(&mut contexts, &mut output, token, column);
+                match pending_branch.take() {
+                    Some(indentation) if column > indentation => {
+                        contexts.push(Context {
+                            kind: ContextKind::Branch,
+                            column,
+                            indentation,
+                        });
+                        output.push(Self::layout_token(TokenType::BlockOpen, token));
+                    }
+                    _ => {
+                        closed_let =
+                            Self::close_contexts(&mut contexts, &mut output, token, column)?;
+
+                        let context = contexts.last().unwrap();
+                        if column == context.column
+                            && matches!(context.kind, ContextKind::TopLevel | ContextKind::Let)
+                            && !Self::continues_line(token)
+                        {
+                            output.push(Self::layout_token(TokenType::BlockSeparator, token));
+                        }
+                    }
+                }
+            }
+
+            // An `in` that starts a line may already have closed its `let`
+            // block above, and must not close the one around it as well.
+            if token.token_type == TokenType::KeywordIn
+                && !closed_let
+                && contexts
+                    .last()
+                    .is_some_and(|context| context.kind == ContextKind::Let)
+            {
+                contexts.pop();
+                output.push(Self::layout_token(TokenType::BlockClose, token));
+            }
+
+            if Self::closes_bracket(token)
+                && contexts
+                    .iter()
+                    .any(|context| context.kind == ContextKind::Bracket)
+            {
+                while let Some(context) = contexts.pop() {
+                    if context.kind == ContextKind::Bracket {
+                        break;
+                    }
+                    output.push(Self::layout_token(TokenType::BlockClose, token));
+                }
+            }
+
+            output.push(token.clone());
+            at_line_start = false;
+
+            match token.token_type {
+                TokenType::KeywordLet => pending_let = Some(line_indentation),
+                TokenType::KeywordIf | TokenType::KeywordWhen | TokenType::KeywordElse => {
+                    pending_branch = Some(line_indentation)
+                }
+                TokenType::LeftBracket
+                | TokenType::LeftSquareBracket
+                | TokenType::LeftCurlyBracket => contexts.push(Context {
+                    kind: ContextKind::Bracket,
+                    column,
+                    indentation: line_indentation,
+                }),
+                _ => {}
+            }
+        }
+
+        if let Some(token) = self.tokens.last() {
+            while contexts.len() > 1 {
+                if contexts.pop().unwrap().kind != ContextKind::Bracket {
+                    output.push(Self::layout_token(TokenType::BlockClose, token));
+                }
+            }
+        }
+
+        Ok(output)
+    }
+
+    fn close_contexts(
+        contexts: &mut Vec<Context>,
+        output: &mut Vec<Token>,
+        token: &Token,
+        column: usize,
+    ) -> Result<bool> {
+        let mut closed_let = false;
+        // Lines inside brackets never close the brackets themselves.
+        while contexts.len() > 1
+            && contexts.last().unwrap().kind != ContextKind::Bracket
+            && column < contexts.last().unwrap().column
+        {
+            let context = contexts.pop().unwrap();
+            if column > context.indentation && !Self::continues_line(token) {
+                return Err(Self::indentation_error(token, "Inconsistent indentation"));
+            }
+            closed_let |= context.kind == ContextKind::Let;
+            output.push(Self::layout_token(TokenType::BlockClose, token));
+        }
+
+        Ok(closed_let)
+    }
+
+    // A bracket left open would otherwise swallow the rest of the file, so a
+    // line that starts a new binding or declaration closes it, and the parser
+    // reports it as unclosed.
+    fn close_brackets(
+        contexts: &mut Vec<Context>,
+        output: &mut Vec<Token>,
+        token: &Token,
+        column: usize,
+    ) {
+        if Self::continues_line(token) || Self::closes_bracket(token) {
+            return;
+        }
+        while let Some(bracket) = contexts
+            .iter()
+            .rposition(|context| context.kind == ContextKind::Bracket)
+        {
+            let block = contexts[..bracket]
+                .iter()
+                .rev()
+                .find(|context| matches!(context.kind, ContextKind::TopLevel | ContextKind::Let));
+            if block.is_some_and(|block| column > block.column) {
+                return;
+            }
+            for context in contexts.drain(bracket..).rev() {
+                if context.kind != ContextKind::Bracket {
+                    output.push(Self::layout_token(TokenType::BlockClose, token));
+                }
+            }
+        }
+    }
+
+    // Lines starting with these keywords finish an expression that began on
+    // an earlier line rather than starting a new item.
+    fn continues_line(token: &Token) -> bool {
+        matches!(
+            token.token_type,
+            TokenType::KeywordIn | TokenType::KeywordElse | TokenType::KeywordWhen
+        )
+    }
+
+    fn closes_bracket(token: &Token) -> bool {
+        matches!(
+            token.token_type,
+            TokenType::RightBracket | TokenType::RightSquareBracket | TokenType::RightCurlyBracket
+        )
+    }
+
+    fn layout_token(token_type: TokenType, token: &Token) -> Token {
+        Token {
+            value: String::new(),
+            token_type,
+            span: Span {
+                end: token.span.start,
+                ..token.span
+            },
+        }
+    }
+
+    fn indentation_error(token: &Token, error_text: &'static str) -> SpiralError<'static> {
+        SpiralError {
+            error_text,
+            help_text: "Line this up with the block it belongs to, or indent it further to continue the line above",
+            span: Span {
+                end: token.span.start + 1,
+                ..token.span
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn should_separate_top_level_declarations() {
+        let result = layout("One = 1\nTwo = 2").unwrap();
+        let expected = vec![
+            "FunctionId",
+            "Equals",
+            "Integer",
+            "BlockSeparator",
+            "FunctionId",
+            "Equals",
+            "Integer",
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn should_continue_indented_lines() {
+        let result = layout("Main = @IO.Print\n  \"Hello World!\"").unwrap();
+        let expected = vec![
+            "FunctionId",
+            "Equals",
+            "NamespaceId",
+            "Period",
+            "FunctionId",
+            "String",
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn should_open_a_block_for_let_bindings() {
+        let result =
+            layout("Main =\n  let\n    twentyFour = 3 * 8\n    sixteen = 4 ^ 2\n  in\n    twentyFour + sixteen")
+                .unwrap();
+        let expected = vec![
+            "FunctionId",
+            "Equals",
+            "KeywordLet",
+            "BlockOpen",
+            "VariableId",
+            "Equals",
+            "Integer",
+            "Star",
+            "Integer",
+            "BlockSeparator",
+            "VariableId",
+            "Equals",
+            "Integer",
+            "Caret",
+            "Integer",
+            "BlockClose",
+            "KeywordIn",
+            "VariableId",
+            "Plus",
+            "VariableId",
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn should_close_let_blocks_on_the_same_line_with_in() {
+        let result = layout("Main = let a = 1 in a").unwrap();
+        let expected = vec![
+            "FunctionId",
+            "Equals",
+            "KeywordLet",
+            "BlockOpen",
+            "VariableId",
+            "Equals",
+            "Integer",
+            "BlockClose",
+            "KeywordIn",
+            "VariableId",
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn should_open_blocks_for_if_branches() {
+        let result = layout(
+            "Main n =\n  if n == 40\n    n + 1\n  else if n == 38\n    n - 1\n  else\n    n",
+        )
+        .unwrap();
+        let expected = vec![
+            "FunctionId",
+            "VariableId",
+            "Equals",
+            "KeywordIf",
+            "VariableId",
+            "DoubleEquals",
+            "Integer",
+            "BlockOpen",
+            "VariableId",
+            "Plus",
+            "Integer",
+            "BlockClose",
+            "KeywordElse",
+            "KeywordIf",
+            "VariableId",
+            "DoubleEquals",
+            "Integer",
+            "BlockOpen",
+            "VariableId",
+            "Dash",
+            "Integer",
+            "BlockClose",
+            "KeywordElse",
+            "BlockOpen",
+            "VariableId",
+            "BlockClose",
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn should_open_blocks_for_match_arms_in_the_top_level_column() {
+        let result = layout("Main n = match n\nwhen 0\n  1\nelse\n  2\nNext = 3").unwrap();
+        let expected = vec![
+            "FunctionId",
+            "VariableId",
+            "Equals",
+            "KeywordMatch",
+            "VariableId",
+            "KeywordWhen",
+            "Integer",
+            "BlockOpen",
+            "Integer",
+            "BlockClose",
+            "KeywordElse",
+            "BlockOpen",
+            "Integer",
+            "BlockClose",
+            "BlockSeparator",
+            "FunctionId",
+            "Equals",
+            "Integer",
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn should_raise_error_for_inconsistent_indentation() {
+        let result = layout("Main =\n  let\n    a = 1\n   b = 2\n  in\n    a");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn should_point_at_the_offending_column() {
+        let error = layout("Main =\n  let\n    a = 1\n   b = 2\n  in\n    a").unwrap_err();
+        assert_eq!((error.span.start, error.span.end), (26, 27));
+    }
+
+    #[test]
+    fn should_raise_error_for_unindented_let_bindings() {
+        let result = layout("Main =\n  let\n  a = 1\n  in\n    a");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn doc_comments_should_not_affect_layout() {
+        let result = layout("One = 1\n--| Two\n  --| indented\nTwo = 2").unwrap();
+        let expected = vec![
+            "FunctionId",
+            "Equals",
+            "Integer",
+            "DocComment",
+            "DocComment",
+            "BlockSeparator",
+            "FunctionId",
+            "Equals",
+            "Integer",
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn should_raise_error_for_doc_comments_at_the_end_of_a_line() {
+        let error = layout("One = 1 --| One\nTwo = 2").unwrap_err();
+        assert_eq!(
+            error.error_text,
+            "Doc comments must be on a line of their own"
+        );
+        assert_eq!((error.span.start, error.span.end), (8, 15));
+    }
+
+    #[test]
+    fn columns_should_count_characters() {
+        let result = layout("Größe = let a = 1\n            b = 2\n        in a").unwrap();
+        let expected = vec![
+            "FunctionId",
+            "Equals",
+            "KeywordLet",
+            "BlockOpen",
+            "VariableId",
+            "Equals",
+            "Integer",
+            "BlockSeparator",
+            "VariableId",
+            "Equals",
+            "Integer",
+            "BlockClose",
+            "KeywordIn",
+            "VariableId",
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn columns_should_restart_after_multi_line_comments() {
+        let result = layout("Main = let {- a\n -} a = 1\n    b = 2\n  in a").unwrap();
+        let expected = vec![
+            "FunctionId",
+            "Equals",
+            "KeywordLet",
+            "BlockOpen",
+            "VariableId",
+            "Equals",
+            "Integer",
+            "BlockSeparator",
+            "VariableId",
+            "Equals",
+            "Integer",
+            "BlockClose",
+            "KeywordIn",
+            "VariableId",
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn should_close_only_the_inner_let_block_with_a_nested_in() {
+        let result = layout(
+            "Main =\n  let\n    a =\n      let\n        b = 1\n      in b\n    c = 2\n  in a + c",
+        )
+        .unwrap();
+        let expected = vec![
+            "FunctionId",
+            "Equals",
+            "KeywordLet",
+            "BlockOpen",
+            "VariableId",
+            "Equals",
+            "KeywordLet",
+            "BlockOpen",
+            "VariableId",
+            "Equals",
+            "Integer",
+            "BlockClose",
+            "KeywordIn",
+            "VariableId",
+            "BlockSeparator",
+            "VariableId",
+            "Equals",
+            "Integer",
+            "BlockClose",
+            "KeywordIn",
+            "VariableId",
+            "Plus",
+            "VariableId",
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn should_close_nested_let_blocks_on_the_same_line_with_in() {
+        let result =
+            layout("Main =\n  let\n    a = let b = 1 in b\n    c = 2\n  in a + c").unwrap();
+        let expected = vec![
+            "FunctionId",
+            "Equals",
+            "KeywordLet",
+            "BlockOpen",
+            "VariableId",
+            "Equals",
+            "KeywordLet",
+            "BlockOpen",
+            "VariableId",
+            "Equals",
+            "Integer",
+            "BlockClose",
+            "KeywordIn",
+            "VariableId",
+            "BlockSeparator",
+            "VariableId",
+            "Equals",
+            "Integer",
+            "BlockClose",
+            "KeywordIn",
+            "VariableId",
+            "Plus",
+            "VariableId",
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn closing_brackets_should_close_the_blocks_inside_them() {
+        let result = layout("Main x = F x (if x\n  1\nelse\n  2)").unwrap();
+        let expected = vec![
+            "FunctionId",
+            "VariableId",
+            "Equals",
+            "FunctionId",
+            "VariableId",
+            "LeftBracket",
+            "KeywordIf",
+            "VariableId",
+            "BlockOpen",
+            "Integer",
+            "BlockClose",
+            "KeywordElse",
+            "BlockOpen",
+            "Integer",
+            "BlockClose",
+            "RightBracket",
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn new_declarations_should_close_brackets_left_open() {
+        let result = layout("Main = F(1\nTwo = [2\n  3\nThree = 3").unwrap();
+        let expected = vec![
+            "FunctionId",
+            "Equals",
+            "FunctionId",
+            "LeftBracket",
+            "Integer",
+            "BlockSeparator",
+            "FunctionId",
+            "Equals",
+            "LeftSquareBracket",
+            "Integer",
+            "Integer",
+            "BlockSeparator",
+            "FunctionId",
+            "Equals",
+            "Integer",
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn new_let_bindings_should_close_brackets_left_open() {
+        let result = layout("Main = let a = F(1\n           b = 2\n       in b").unwrap();
+        let expected = vec![
+            "FunctionId",
+            "Equals",
+            "KeywordLet",
+            "BlockOpen",
+            "VariableId",
+            "Equals",
+            "FunctionId",
+            "LeftBracket",
+            "Integer",
+            "BlockSeparator",
+            "VariableId",
+            "Equals",
+            "Integer",
+            "BlockClose",
+            "KeywordIn",
+            "VariableId",
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn lines_inside_brackets_should_not_separate_declarations() {
+        let result = layout("Main = F [1\n  2]\nNext = 3").unwrap();
+        let expected = vec![
+            "FunctionId",
+            "Equals",
+            "FunctionId",
+            "LeftSquareBracket",
+            "Integer",
+            "Integer",
+            "RightSquareBracket",
+            "BlockSeparator",
+            "FunctionId",
+            "Equals",
+            "Integer",
+        ];
+        assert_eq!(result, expected);
+    }
+
+    fn layout(input: &str) -> super::Result<Vec<String>> {
+        let mut t = super::super::tokenizer::Tokenizer::build(Default::default(), input);
+        let tokens = t.execute()?;
+        let mut l = super::Layout::build(input, tokens);
+        let mut output = Vec::new();
+        for token in l.execute()? {
+            if token.token_type != super::TokenType::Whitespace {
+                output.push(format!("{:?}", token.token_type));
+            }
+        }
+        Ok(output)
+    }
+}
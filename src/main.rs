@@ -1,22 +1,26 @@
 // use inkwell::context::Context;
-use std::error::Error;
+mod layout;
 mod parser;
+mod source_map;
+mod span;
 mod spiral_error;
 mod tokenizer;
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let mut t = tokenizer::Tokenizer::build("3 + 4 * (5 + 6)");
-    let tokens = t.execute()?;
+type SpiralError<'a> = spiral_error::SpiralError<'a>;
 
-    // for token in tokens {
-    //     println!("{:?} - {}", token.token_type, token.value);
-    // }
+fn main() {
+    let mut source_map = source_map::SourceMap::new();
+    let file_id = source_map.add("main.spiral", "Main = 3 + 4 * (5 + 6)");
 
-    let mut p = parser::Parser::build(tokens);
-    let node = p.execute()?;
-    println!("{:?}", node);
-
-    Ok(())
+    match compile(&source_map, file_id) {
+        Ok(program) => println!("{}", program),
+        Err(errors) => {
+            for error in errors {
+                eprintln!("{}\n", error.render(&source_map));
+            }
+            std::process::exit(1);
+        }
+    }
     // let context = Context::create();
     // let module = context.create_module("test");
     // let builder = context.create_builder();
@@ -41,3 +45,25 @@ fn main() -> Result<(), Box<dyn Error>> {
     // module.print_to_file("outrs.ll").ok();
     // Ok(())
 }
+
+fn compile(
+    source_map: &source_map::SourceMap,
+    file_id: source_map::FileId,
+) -> Result<parser::ProgramNode, Vec<SpiralError<'static>>> {
+    let mut t = tokenizer::Tokenizer::build(file_id, source_map.source(file_id));
+    let tokens = t.execute().map_err(|error| vec![error])?;
+
+    // for token in tokens {
+    //     println!("{:?} - {}", token.token_type, token.value);
+    // }
+
+    let mut l = layout::Layout::build(source_map.source(file_id), tokens);
+    let tokens = l.execute().map_err(|error| vec![error])?;
+
+    let mut p = parser::Parser::build(tokens);
+    let (program, errors) = p.execute();
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    Ok(program)
+}
@@ -1,55 +1,269 @@
-use std::error;
+mod ast;
 mod bin_op_node;
+mod bool_node;
+mod call_node;
+mod char_node;
+mod exposing_node;
+mod field_access_node;
+mod float_node;
+mod function_definition_node;
+mod function_node;
+mod if_node;
+mod import_node;
 mod int_node;
+mod lambda_node;
+mod let_node;
+mod list_node;
+mod match_node;
+mod module_header_node;
 mod node;
+mod pattern_node;
+mod precedence;
+mod program_node;
+mod signature_node;
+mod string_node;
+mod struct_literal_node;
+mod struct_type_node;
+mod struct_update_node;
+mod type_definition_node;
+mod type_node;
+mod type_variable_node;
 mod unary_op_node;
+mod union_type_node;
+mod variable_node;
+// The traversal traits are the base for passes over the AST; nothing in the
+// compiler walks the tree yet.
+#[allow(dead_code)]
+mod visitor;
 
-type Result<T> = std::result::Result<T, Box<dyn error::Error>>;
+use ast::{Decl, Expr, Identifier, Literal, Pattern, Type};
+pub use program_node::ProgramNode;
+
+type Result<T> = std::result::Result<T, SpiralError<'static>>;
 type SpiralError<'a> = super::spiral_error::SpiralError<'a>;
+type Span = super::span::Span;
+type Token = super::tokenizer::Token;
 type TokenType = super::tokenizer::TokenType;
 
 pub struct Parser {
     pub current_index: usize,
-    pub tokens: Vec<super::tokenizer::Token>,
+    pub tokens: Vec<Token>,
+    pub errors: Vec<SpiralError<'static>>,
 }
 
 impl Parser {
-    pub fn build(tokens: Vec<super::tokenizer::Token>) -> Parser {
+    pub fn build(tokens: Vec<Token>) -> Parser {
         Parser {
             current_index: 0,
             tokens,
+            errors: Vec::new(),
+        }
+    }
+
+    // Syntax errors are collected rather than returned, so that every error
+    // in the file can be reported at once. Declarations and bracketed
+    // expressions that fail to parse are replaced with error nodes.
+    pub fn execute(&mut self) -> (program_node::ProgramNode, Vec<SpiralError<'static>>) {
+        self.skip_whitespace();
+
+        let header = if self.current_is(TokenType::KeywordNamespace) {
+            let start_index = self.current_index;
+            match self.module_header() {
+                Ok(header) => Some(header),
+                Err(error) => {
+                    self.errors.push(error);
+                    self.synchronise(start_index);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let mut imports = Vec::new();
+        let mut declarations = Vec::new();
+        let mut is_first_item = header.is_none() && self.errors.is_empty();
+        while self.current_token().is_some() {
+            if !is_first_item {
+                if let Err(error) = self.expect(
+                    TokenType::BlockSeparator,
+                    "Unexpected token",
+                    "Each declaration must begin on a new line, in the same column as the others",
+                ) {
+                    self.errors.push(error);
+                    self.synchronise(self.current_index);
+                    continue;
+                }
+            }
+            is_first_item = false;
+
+            let start_index = self.current_index;
+            let start = self.current_span();
+            if !self.current_is(TokenType::KeywordImport) {
+                match self.declaration() {
+                    Ok(declaration) => declarations.push(declaration),
+                    Err(error) => {
+                        self.errors.push(error);
+                        self.synchronise(start_index);
+                        declarations.push(Decl::Error(self.span_from(start)));
+                    }
+                }
+            } else if declarations.is_empty() {
+                match self.import() {
+                    Ok(import) => imports.push(import),
+                    Err(error) => {
+                        self.errors.push(error);
+                        self.synchronise(start_index);
+                    }
+                }
+            } else {
+                self.errors.push(self.error(
+                    "Imports must come before any definitions",
+                    "Move this import to the top of the file, below the namespace header",
+                ));
+                self.synchronise(start_index);
+            }
+        }
+
+        let program = program_node::ProgramNode {
+            header,
+            imports,
+            declarations,
+        };
+        (program, std::mem::take(&mut self.errors))
+    }
+
+    // Skips to the separator in front of the next top level item. Layout
+    // blocks are counted from the start of the failed item so that the
+    // separators between its let bindings are not mistaken for the end of it.
+    fn synchronise(&mut self, start_index: usize) {
+        let mut depth = 0;
+        let mut index = start_index;
+        while let Some(token) = self.tokens.get(index) {
+            match token.token_type {
+                TokenType::BlockOpen => depth += 1,
+                TokenType::BlockClose => depth -= 1,
+                TokenType::BlockSeparator if depth == 0 && index >= self.current_index => break,
+                _ => {}
+            }
+            index += 1;
         }
+        self.current_index = index;
     }
 
-    pub fn execute(&mut self) -> Result<Box<dyn node::Node>> {
-        self.expression()
+    // Records `error` and replaces the bracketed expression it occurred in
+    // with an error node, provided the bracket is closed before the end of
+    // the current line of layout. Otherwise the error is left to the caller.
+    fn recover_at_closing(
+        &mut self,
+        error: SpiralError<'static>,
+        closing: TokenType,
+        start: Span,
+    ) -> Result<Expr> {
+        let mut depth = 0;
+        let mut layout_depth = 0;
+        for index in self.current_index..self.tokens.len() {
+            let token_type = &self.tokens[index].token_type;
+            if *token_type == closing && depth == 0 {
+                self.current_index = index;
+                self.advance_through_whitespace();
+                self.errors.push(error);
+                return Ok(Expr::Error(self.span_from(start)));
+            }
+
+            match token_type {
+                TokenType::LeftBracket
+                | TokenType::LeftSquareBracket
+                | TokenType::LeftCurlyBracket => depth += 1,
+                TokenType::RightBracket
+                | TokenType::RightSquareBracket
+                | TokenType::RightCurlyBracket => {
+                    if depth == 0 {
+                        break;
+                    }
+                    depth -= 1;
+                }
+                TokenType::BlockOpen => layout_depth += 1,
+                TokenType::BlockSeparator | TokenType::BlockClose if layout_depth == 0 => break,
+                TokenType::BlockClose => layout_depth -= 1,
+                _ => {}
+            }
+        }
+
+        Err(error)
     }
 
-    fn current_token(&self) -> Option<&super::tokenizer::Token> {
+    fn current_token(&self) -> Option<&Token> {
         self.tokens.get(self.current_index)
     }
 
-    fn previous_token(&self) -> Option<&super::tokenizer::Token> {
-        if self.current_index > 1 {
+    fn current_is(&self, token_type: TokenType) -> bool {
+        self.current_token()
+            .is_some_and(|token| token.token_type == token_type)
+    }
+
+    fn current_span(&self) -> Span {
+        self.current_token()
+            .map_or(Span::default(), |token| token.span)
+    }
+
+    fn next_significant_token(&self) -> Option<&Token> {
+        self.tokens[self.current_index + 1..]
+            .iter()
+            .find(|token| !Self::is_trivia(token))
+    }
+
+    fn preceded_by_whitespace(&self) -> bool {
+        self.current_index > 0 && Self::is_trivia(&self.tokens[self.current_index - 1])
+    }
+
+    fn previous_token(&self) -> Option<&Token> {
+        if self.current_index > 0 {
             self.tokens.get(self.current_index - 1)
         } else {
             None
         }
     }
 
+    // Extends `start` to the end of the last token consumed, skipping the
+    // whitespace, doc comments and zero-width layout tokens that follow it.
+    fn span_from(&self, start: Span) -> Span {
+        self.tokens[..self.current_index]
+            .iter()
+            .rev()
+            .find(|token| !Self::is_trivia(token) && !Self::is_layout(token))
+            .map_or(start, |token| start.to(token.span))
+    }
+
     fn advance(&mut self) {
         self.current_index += 1;
     }
 
     fn advance_through_whitespace(&mut self) {
         self.advance();
+        self.skip_whitespace();
+    }
+
+    // Doc comments are only read back by `documentation`, anywhere else
+    // they are skipped like whitespace.
+    fn is_trivia(token: &Token) -> bool {
+        matches!(
+            token.token_type,
+            TokenType::Whitespace | TokenType::Newline | TokenType::DocComment
+        )
+    }
+
+    // The zero-width tokens inserted by the layout pass.
+    fn is_layout(token: &Token) -> bool {
+        matches!(
+            token.token_type,
+            TokenType::BlockOpen | TokenType::BlockSeparator | TokenType::BlockClose
+        )
+    }
+
+    fn skip_whitespace(&mut self) {
         let mut token = self.current_token();
-        while token.is_some()
-            && matches!(
-                token.unwrap().token_type,
-                TokenType::Whitespace | TokenType::Newline
-            )
-        {
+        while token.is_some() && Self::is_trivia(token.unwrap()) {
             self.advance();
             token = self.current_token();
             if token.is_none() {
@@ -58,119 +272,2501 @@ impl Parser {
         }
     }
 
-    fn factor(&mut self) -> Result<Box<dyn node::Node>> {
-        let token_result = self.current_token();
-        if !token_result.is_some() {
-            return Err(Box::new(SpiralError {
-                error_text: "Expected a factor. Valid Factors are: Int",
-                help_text: "",
-                line_text: self.previous_token().unwrap().line.clone(),
-                begin: self.previous_token().unwrap().begin,
-                end: self.previous_token().unwrap().end,
-                line_number: self.previous_token().unwrap().line_number,
-            }));
+    fn expect(
+        &mut self,
+        token_type: TokenType,
+        error_text: &'static str,
+        help_text: &'static str,
+    ) -> Result<Token> {
+        if !self.current_is(token_type) {
+            return Err(self.error(error_text, help_text));
+        }
+
+        let token = self.current_token().unwrap().clone();
+        self.advance_through_whitespace();
+        Ok(token)
+    }
+
+    // Layout tokens take the position of the next line's first token, so
+    // when the line or the input has ended the error is placed just after
+    // the last token that was actually written.
+    fn error(&self, error_text: &'static str, help_text: &'static str) -> SpiralError<'static> {
+        match self.current_token() {
+            Some(token) if !Self::is_layout(token) => Self::error_at(token, error_text, help_text),
+            _ => SpiralError {
+                error_text,
+                help_text,
+                span: self.tokens[..self.current_index.min(self.tokens.len())]
+                    .iter()
+                    .rev()
+                    .find(|token| !Self::is_trivia(token) && !Self::is_layout(token))
+                    .map_or(Span::default(), |token| Span {
+                        start: token.span.end,
+                        ..token.span
+                    }),
+            },
+        }
+    }
+
+    fn error_at(
+        token: &Token,
+        error_text: &'static str,
+        help_text: &'static str,
+    ) -> SpiralError<'static> {
+        SpiralError {
+            error_text,
+            help_text,
+            span: token.span,
+        }
+    }
+
+    fn identifier(token: &Token) -> Identifier {
+        Identifier {
+            name: token.value.clone(),
+            span: token.span,
+        }
+    }
+
+    // Field tokens include their trailing ':', which is not part of the name.
+    fn field_name(token: &Token) -> Identifier {
+        let span = token.span;
+        Identifier {
+            name: token.value.trim_end_matches(':').to_string(),
+            span: Span {
+                end: span.end - 1,
+                ..span
+            },
+        }
+    }
+
+    fn module_header(&mut self) -> Result<module_header_node::ModuleHeaderNode> {
+        let start = self.expect(
+            TokenType::KeywordNamespace,
+            "Expected 'namespace'",
+            "A module header begins with the 'namespace' keyword",
+        )?;
+        let namespace = self.expect(
+            TokenType::NamespaceId,
+            "Expected a namespace name",
+            "Namespaces begin with '@' followed by a capital letter, e.g. @Maths",
+        )?;
+        self.expect(
+            TokenType::KeywordExposing,
+            "Expected 'exposing' after the namespace name",
+            "Declare what the namespace exports, e.g. exposing [Function variable #Type] or exposing _",
+        )?;
+        let exposing = self.exposing()?;
+
+        Ok(module_header_node::ModuleHeaderNode {
+            namespace: Self::identifier(&namespace),
+            exposing,
+            span: self.span_from(start.span),
+        })
+    }
+
+    fn import(&mut self) -> Result<import_node::ImportNode> {
+        let start = self.expect(
+            TokenType::KeywordImport,
+            "Expected 'import'",
+            "Imports begin with the 'import' keyword",
+        )?;
+        let namespace = self.expect(
+            TokenType::NamespaceId,
+            "Expected a namespace to import",
+            "Namespaces begin with '@' followed by a capital letter, e.g. import @Maths",
+        )?;
+
+        let exposing = if self.current_is(TokenType::KeywordExposing) {
+            self.advance_through_whitespace();
+            Some(self.exposing()?)
+        } else {
+            None
+        };
+
+        Ok(import_node::ImportNode {
+            namespace: Self::identifier(&namespace),
+            exposing,
+            span: self.span_from(start.span),
+        })
+    }
+
+    fn exposing(&mut self) -> Result<exposing_node::ExposingNode> {
+        let start = self.current_span();
+        if self.current_is(TokenType::Underscore) {
+            self.advance_through_whitespace();
+            return Ok(exposing_node::ExposingNode::All(start));
+        }
+
+        self.expect(
+            TokenType::LeftSquareBracket,
+            "Expected an exposing list",
+            "Use [Function variable #Type] to expose specific items or _ to expose everything",
+        )?;
+
+        let mut names = Vec::new();
+        while !self.current_is(TokenType::RightSquareBracket) {
+            let token = self.current_token().ok_or_else(|| {
+                self.error(
+                    "Unclosed exposing list",
+                    "Please close the exposing list with ']'",
+                )
+            })?;
+
+            if !matches!(
+                token.token_type,
+                TokenType::FunctionId | TokenType::VariableId | TokenType::TypeId
+            ) {
+                return Err(self.error(
+                    "Only functions, variables and types can be exposed",
+                    "Separate exposed items with spaces, e.g. [Function variable #Type]",
+                ));
+            }
+
+            names.push(Self::identifier(token));
+            self.advance_through_whitespace();
+        }
+        self.advance_through_whitespace();
+
+        Ok(exposing_node::ExposingNode::Only(
+            names,
+            self.span_from(start),
+        ))
+    }
+
+    fn declaration(&mut self) -> Result<Decl> {
+        let documentation = self.documentation();
+        if self.current_is(TokenType::TypeId) {
+            return Ok(Decl::Type(self.type_definition(documentation)?));
+        }
+
+        if !self.current_is(TokenType::FunctionId) {
+            return Err(self.error(
+                "Expected a declaration",
+                "Declarations are type signatures (Name : #Type), function definitions (Name = ...) or type definitions (#Name = ...)",
+            ));
+        }
+
+        let is_signature = self
+            .next_significant_token()
+            .is_some_and(|token| token.token_type == TokenType::Colon);
+        if !is_signature {
+            return Ok(Decl::Function(
+                self.function_definition(None, documentation)?,
+            ));
+        }
+
+        let name_token = self.current_token().unwrap().clone();
+        let signature = self.signature()?;
+        let has_definition = self.current_is(TokenType::BlockSeparator)
+            && self.next_significant_token().is_some_and(|token| {
+                token.token_type == TokenType::FunctionId && token.value == signature.name.name
+            });
+        if !has_definition {
+            return Err(Self::error_at(
+                &name_token,
+                "Type signature has no matching definition",
+                "A type signature must be directly followed by the definition of the same function",
+            ));
+        }
+        self.advance_through_whitespace();
+
+        // Doc comments between the signature and the definition document the
+        // function too.
+        let documentation = match (documentation, self.documentation()) {
+            (Some(before), Some(after)) => Some(format!("{}\n{}", before, after)),
+            (before, after) => before.or(after),
+        };
+        Ok(Decl::Function(
+            self.function_definition(Some(signature), documentation)?,
+        ))
+    }
+
+    // Doc comments are skipped along with whitespace, so the ones belonging
+    // to a declaration are found by looking back from its first token.
+    fn documentation(&self) -> Option<String> {
+        let mut lines = Vec::new();
+        for token in self.tokens[..self.current_index].iter().rev() {
+            match token.token_type {
+                TokenType::DocComment => lines.push(token.value.as_str()),
+                TokenType::Whitespace
+                | TokenType::Newline
+                | TokenType::BlockSeparator
+                | TokenType::BlockClose => {}
+                _ => break,
+            }
+        }
+
+        if lines.is_empty() {
+            return None;
+        }
+        lines.reverse();
+        Some(lines.join("\n"))
+    }
+
+    fn signature(&mut self) -> Result<signature_node::SignatureNode> {
+        let name = self.expect(
+            TokenType::FunctionId,
+            "Expected a function name",
+            "Function names begin with a capital letter",
+        )?;
+        self.expect(
+            TokenType::Colon,
+            "Expected ':' after the function name",
+            "Type signatures look like: Name : #Type",
+        )?;
+        let type_node = self.type_expression()?;
+
+        Ok(signature_node::SignatureNode {
+            name: Self::identifier(&name),
+            type_node,
+            span: self.span_from(name.span),
+        })
+    }
+
+    fn function_definition(
+        &mut self,
+        signature: Option<signature_node::SignatureNode>,
+        documentation: Option<String>,
+    ) -> Result<function_definition_node::FunctionDefinitionNode> {
+        let name = self.expect(
+            TokenType::FunctionId,
+            "Expected a function name",
+            "Function names begin with a capital letter",
+        )?;
+
+        let mut parameters = Vec::new();
+        while self.starts_pattern() {
+            parameters.push(self.irrefutable_pattern()?);
+        }
+
+        self.expect(
+            TokenType::Equals,
+            "Expected '=' in function definition",
+            "Function definitions look like: Name parameter = expression",
+        )?;
+        let body = self.expression()?;
+
+        Ok(function_definition_node::FunctionDefinitionNode {
+            span: self.span_from(signature.as_ref().map_or(name.span, |s| s.span)),
+            documentation,
+            signature,
+            name: Self::identifier(&name),
+            parameters,
+            body,
+        })
+    }
+
+    fn type_definition(
+        &mut self,
+        documentation: Option<String>,
+    ) -> Result<type_definition_node::TypeDefinitionNode> {
+        let name = self.expect(
+            TokenType::TypeId,
+            "Expected a type name",
+            "Type names begin with '#' followed by a capital letter",
+        )?;
+
+        let mut parameters = Vec::new();
+        if self.current_is(TokenType::LessThan) {
+            self.advance_through_whitespace();
+            loop {
+                let parameter = self.expect(
+                    TokenType::VariableId,
+                    "Expected a type parameter",
+                    "Type parameters begin with a lowercase letter, e.g. #Pair<a, b>",
+                )?;
+                parameters.push(Self::identifier(&parameter));
+                if !self.current_is(TokenType::Comma) {
+                    break;
+                }
+                self.advance_through_whitespace();
+            }
+            self.expect(
+                TokenType::GreaterThan,
+                "Unclosed type parameters",
+                "Please close the type parameters with '>'",
+            )?;
+        }
+
+        self.expect(
+            TokenType::Equals,
+            "Expected '=' in type definition",
+            "Type definitions look like: #Name = #Type",
+        )?;
+        let type_node = self.type_expression()?;
+
+        Ok(type_definition_node::TypeDefinitionNode {
+            documentation,
+            name: Self::identifier(&name),
+            parameters,
+            type_node,
+            span: self.span_from(name.span),
+        })
+    }
+
+    fn type_expression(&mut self) -> Result<Type> {
+        let start = self.current_span();
+        let first = self.type_term()?;
+        if !self.current_is(TokenType::Or) {
+            return Ok(first);
         }
-        let token = token_result.unwrap();
 
-        if matches!(token.token_type, TokenType::Plus | TokenType::Dash) {
-            let cloned_token = token.clone();
+        let mut types = vec![first];
+        while self.current_is(TokenType::Or) {
             self.advance_through_whitespace();
-            let node = unary_op_node::UnaryOpNode {
-                token: cloned_token,
-                node: self.factor()?,
-            };
-            return Ok(Box::new(node));
+            types.push(self.type_term()?);
         }
 
-        if matches!(token.token_type, TokenType::Integer) {
-            let node = int_node::IntNode {
-                token: token.clone(),
-            };
+        Ok(Type::Union(union_type_node::UnionTypeNode {
+            types,
+            span: self.span_from(start),
+        }))
+    }
+
+    fn type_term(&mut self) -> Result<Type> {
+        if self.current_is(TokenType::VariableId) {
+            let name = Self::identifier(self.current_token().unwrap());
             self.advance_through_whitespace();
-            return Ok(Box::new(node));
+            return Ok(Type::Variable(type_variable_node::TypeVariableNode {
+                name,
+            }));
         }
 
-        if matches!(token.token_type, TokenType::LeftBracket) {
-            let cloned_token = token.clone();
+        let token = self.expect(
+            TokenType::TypeId,
+            "Expected a type",
+            "Types begin with '#' followed by a capital letter, e.g. #Int",
+        )?;
+
+        if token.value == "#Struct" {
+            return Ok(Type::Struct(self.struct_type(&token)?));
+        }
+
+        let mut arguments = Vec::new();
+        if self.current_is(TokenType::LessThan) {
             self.advance_through_whitespace();
-            let expression = self.expression()?;
+            arguments.push(self.type_expression()?);
+            while self.current_is(TokenType::Comma) {
+                self.advance_through_whitespace();
+                arguments.push(self.type_expression()?);
+            }
+            self.expect(
+                TokenType::GreaterThan,
+                "Unclosed type arguments",
+                "Please close the type arguments with '>'",
+            )?;
+        }
 
-            let t_result = self.current_token();
-            if !t_result.is_some() {
-                return Err(Box::new(SpiralError {
-                    error_text: "Unclosed bracket",
-                    help_text: "Please close the bracket",
-                    line_text: cloned_token.line.to_string(),
-                    begin: cloned_token.begin,
-                    end: self.previous_token().unwrap().end,
-                    line_number: cloned_token.line_number,
-                }));
+        Ok(Type::Named(type_node::TypeNode {
+            name: Self::identifier(&token),
+            arguments,
+            span: self.span_from(token.span),
+        }))
+    }
+
+    fn struct_type(&mut self, struct_token: &Token) -> Result<struct_type_node::StructTypeNode> {
+        self.expect(
+            TokenType::LessThan,
+            "Expected struct fields",
+            "Struct types look like: #Struct<x: #Int, y: #Int>",
+        )?;
+
+        let mut fields: Vec<(Identifier, Type)> = Vec::new();
+        loop {
+            let token = self.expect(
+                TokenType::FieldId,
+                "Expected a field name",
+                "Struct fields look like: name: #Type",
+            )?;
+            let name = Self::field_name(&token);
+            if fields.iter().any(|(field, _)| field.name == name.name) {
+                return Err(Self::error_at(
+                    &token,
+                    "Duplicate field name in struct type",
+                    "Each field of a struct may only be declared once",
+                ));
+            }
+            let field_type = self.type_expression()?;
+            fields.push((name, field_type));
+
+            if !self.current_is(TokenType::Comma) {
+                break;
             }
+            self.advance_through_whitespace();
+        }
+
+        self.expect(
+            TokenType::GreaterThan,
+            "Unclosed struct type",
+            "Please close the struct type with '>'",
+        )?;
+
+        Ok(struct_type_node::StructTypeNode {
+            fields,
+            span: self.span_from(struct_token.span),
+        })
+    }
+
+    fn factor(&mut self) -> Result<Expr> {
+        let token = self
+            .current_token()
+            .ok_or_else(|| self.error(
+                "Expected an expression",
+                "Expressions are values like 1, \"text\" or [1 2], names, calls, or let, if and match expressions",
+            ))?;
+
+        if matches!(token.token_type, TokenType::KeywordIf) {
+            return Ok(Expr::If(self.if_expression()?));
+        }
+
+        if matches!(token.token_type, TokenType::KeywordMatch) {
+            return Ok(Expr::Match(self.match_expression()?));
+        }
+
+        if matches!(token.token_type, TokenType::KeywordLet) {
+            return Ok(Expr::Let(self.let_expression()?));
+        }
+
+        if matches!(token.token_type, TokenType::Backslash) {
+            return Ok(Expr::Lambda(self.lambda_expression()?));
+        }
+
+        self.application()
+    }
+
+    // Functions are curried, so a call may supply fewer arguments than the
+    // function takes; `Add 1` is then a function waiting for the rest and
+    // can be passed around or used on the right of `|>`.
+    fn application(&mut self) -> Result<Expr> {
+        let is_applicable = self.current_token().is_some_and(|token| {
+            matches!(
+                token.token_type,
+                TokenType::FunctionId
+                    | TokenType::NamespaceId
+                    | TokenType::VariableId
+                    | TokenType::LeftBracket
+            )
+        });
+        let function = self.atom()?;
+        if !is_applicable {
+            return Ok(function);
+        }
+
+        let mut arguments = Vec::new();
+        while self.starts_argument() {
+            arguments.push(self.atom()?);
+        }
+
+        if arguments.is_empty() {
+            Ok(function)
+        } else {
+            Ok(Expr::Call(call_node::CallNode {
+                span: self.span_from(function.span()),
+                function: Box::new(function),
+                arguments,
+            }))
+        }
+    }
+
+    fn starts_argument(&self) -> bool {
+        self.current_token().is_some_and(|token| {
+            matches!(
+                token.token_type,
+                TokenType::Integer
+                    | TokenType::Float
+                    | TokenType::Char
+                    | TokenType::String
+                    | TokenType::StringStart
+                    | TokenType::KeywordTrue
+                    | TokenType::KeywordFalse
+                    | TokenType::LeftSquareBracket
+                    | TokenType::LeftCurlyBracket
+                    | TokenType::VariableId
+                    | TokenType::FunctionId
+                    | TokenType::NamespaceId
+                    | TokenType::LeftBracket
+            )
+        })
+    }
+
+    fn atom(&mut self) -> Result<Expr> {
+        let mut node = self.primary()?;
+
+        while self.current_is(TokenType::Period) && !self.preceded_by_whitespace() {
+            self.advance_through_whitespace();
+            let field = self.expect(
+                TokenType::VariableId,
+                "Expected a field name after '.'",
+                "Field access looks like: point.x",
+            )?;
+            node = Expr::FieldAccess(field_access_node::FieldAccessNode {
+                span: node.span().to(field.span),
+                node: Box::new(node),
+                field: Self::identifier(&field),
+            });
+        }
+
+        Ok(node)
+    }
+
+    fn primary(&mut self) -> Result<Expr> {
+        let token = self
+            .current_token()
+            .ok_or_else(|| self.error(
+                "Expected a factor",
+                "Expressions are values like 1, \"text\" or [1 2], names, calls, or let, if and match expressions",
+            ))?;
+
+        if matches!(
+            token.token_type,
+            TokenType::Integer
+                | TokenType::Float
+                | TokenType::Char
+                | TokenType::String
+                | TokenType::KeywordTrue
+                | TokenType::KeywordFalse
+        ) {
+            return self.literal();
+        }
+
+        if matches!(token.token_type, TokenType::StringStart) {
+            return self.interpolated_string();
+        }
+
+        if matches!(token.token_type, TokenType::LeftSquareBracket) {
+            let start = token.span;
+            return self.list().map(Expr::List).or_else(|error| {
+                self.recover_at_closing(error, TokenType::RightSquareBracket, start)
+            });
+        }
+
+        if matches!(token.token_type, TokenType::LeftCurlyBracket) {
+            let start = token.span;
+            return self.struct_expression().or_else(|error| {
+                self.recover_at_closing(error, TokenType::RightCurlyBracket, start)
+            });
+        }
+
+        if matches!(token.token_type, TokenType::VariableId) {
+            let name = Self::identifier(token);
+            self.advance_through_whitespace();
+            return Ok(Expr::Variable(variable_node::VariableNode { name }));
+        }
+
+        if matches!(
+            token.token_type,
+            TokenType::FunctionId | TokenType::NamespaceId
+        ) {
+            return self.function_reference();
+        }
+
+        if matches!(token.token_type, TokenType::LeftBracket) {
+            let start = token.span;
+            return self
+                .bracketed_expression()
+                .or_else(|error| self.recover_at_closing(error, TokenType::RightBracket, start));
+        }
 
-            let t = t_result.unwrap();
+        Err(self.error(
+            "Unknown Factor",
+            "Expressions are values like 1, \"text\" or [1 2], names, calls, or let, if and match expressions",
+        ))
+    }
+
+    fn bracketed_expression(&mut self) -> Result<Expr> {
+        let cloned_token = self.current_token().unwrap().clone();
+        self.advance_through_whitespace();
+        let expression = self.expression()?;
 
-            if matches!(t.token_type, TokenType::RightBracket) {
+        match self.current_token() {
+            Some(token) if token.token_type == TokenType::RightBracket => {
                 self.advance_through_whitespace();
-                return Ok(expression);
-            } else {
-                return Err(Box::new(SpiralError {
-                    error_text: "Unclosed bracket",
-                    help_text: "Please close the bracket",
-                    line_text: cloned_token.line.to_string(),
-                    begin: cloned_token.begin,
-                    end: t.end,
-                    line_number: cloned_token.line_number,
+                Ok(expression)
+            }
+            Some(token) => Err(SpiralError {
+                error_text: "Unclosed bracket",
+                help_text: "Please close the bracket",
+                span: cloned_token.span.to(token.span),
+            }),
+            None => Err(SpiralError {
+                error_text: "Unclosed bracket",
+                help_text: "Please close the bracket",
+                span: cloned_token.span.to(self.previous_token().unwrap().span),
+            }),
+        }
+    }
+
+    fn literal(&mut self) -> Result<Expr> {
+        let token = self.current_token().unwrap().clone();
+        let span = token.span;
+        let node = match Self::literal_value(&token)? {
+            Literal::Int(value) => Expr::Int(int_node::IntNode { value, span }),
+            Literal::Float(value) => Expr::Float(float_node::FloatNode { value, span }),
+            Literal::Char(value) => Expr::Char(char_node::CharNode { value, span }),
+            Literal::String(value) => Expr::String(string_node::StringNode { value, span }),
+            Literal::Bool(value) => Expr::Bool(bool_node::BoolNode { value, span }),
+        };
+
+        self.advance_through_whitespace();
+        Ok(node)
+    }
+
+    // "Hello {name}!" is desugared to "Hello " ++ @String.From(name) ++ "!".
+    // Every interpolated value goes through @String.From, which converts
+    // #Int, #Float and #Char values and leaves strings as they are.
+    fn interpolated_string(&mut self) -> Result<Expr> {
+        let mut parts = Vec::new();
+        loop {
+            let token = self.current_token().unwrap().clone();
+            if !token.value.is_empty() {
+                parts.push(Expr::String(string_node::StringNode {
+                    value: token.value.clone(),
+                    span: token.span,
                 }));
             }
+            self.advance_through_whitespace();
+            if token.token_type == TokenType::StringEnd {
+                break;
+            }
+
+            let expression = self.expression()?;
+            parts.push(Self::string_conversion(expression));
+            if !matches!(
+                self.current_token().map(|token| &token.token_type),
+                Some(TokenType::StringMiddle | TokenType::StringEnd)
+            ) {
+                return Err(self.error(
+                    "Expected '}' after the interpolated expression",
+                    "Interpolations contain a single expression, e.g. \"Hello {name}\"",
+                ));
+            }
         }
 
-        return Err(Box::new(SpiralError {
-            error_text: "Unknown Factor",
-            help_text: "",
-            line_text: self.previous_token().unwrap().line.clone(),
-            begin: self.previous_token().unwrap().begin,
-            end: self.previous_token().unwrap().end,
-            line_number: self.previous_token().unwrap().line_number,
-        }));
+        // `++` is right associative, so the parts are joined from the right
+        // to give the same tree as writing the concatenation out by hand.
+        let mut parts = parts.into_iter().rev();
+        let last = parts.next().unwrap();
+        Ok(parts.fold(last, |right, left| {
+            Expr::BinOp(bin_op_node::BinOpNode {
+                span: left.span().to(right.span()),
+                left_node: Box::new(left),
+                right_node: Box::new(right),
+                operator: bin_op_node::BinaryOperator::Concatenate,
+            })
+        }))
     }
 
-    fn term(&mut self) -> Result<Box<dyn node::Node>> {
-        self.bin_op_node(vec![TokenType::Star, TokenType::ForwardSlash], "factor")
+    fn string_conversion(expression: Expr) -> Expr {
+        let span = expression.span();
+        let identifier = |name: &str| Identifier {
+            name: name.to_string(),
+            span,
+        };
+        Expr::Call(call_node::CallNode {
+            function: Box::new(Expr::Function(function_node::FunctionNode {
+                namespace: Some(identifier("@String")),
+                name: identifier("From"),
+                span,
+            })),
+            arguments: vec![expression],
+            span,
+        })
     }
 
-    fn bin_op_node(&mut self, types: Vec<TokenType>, func: &str) -> Result<Box<dyn node::Node>> {
-        let mut left = self.bin_op_function(func)?;
+    fn literal_value(token: &Token) -> Result<Literal> {
+        match token.token_type {
+            // The tokenizer lets through 9223372036854775808 so that it can
+            // be negated, see `negated_minimum`.
+            TokenType::Integer => token.value.parse().map(Literal::Int).map_err(|_| {
+                Self::error_at(
+                    token,
+                    "Integer literal is too large",
+                    "Integers must be between -9223372036854775808 and 9223372036854775807",
+                )
+            }),
+            TokenType::Float => token.value.parse().map(Literal::Float).map_err(|_| {
+                Self::error_at(token, "Invalid float literal", "Floats look like: 3.14")
+            }),
+            // The tokenizer has already checked that a char holds exactly one
+            // character and decoded its escapes.
+            TokenType::Char => Ok(Literal::Char(token.value.chars().next().unwrap())),
+            TokenType::String => Ok(Literal::String(token.value.clone())),
+            _ => Ok(Literal::Bool(token.token_type == TokenType::KeywordTrue)),
+        }
+    }
 
-        while self.current_token().is_some()
-            && types
-                .iter()
-                .any(|t| t == &self.current_token().unwrap().token_type)
-        {
-            let operation_token = self.current_token().unwrap().clone();
+    // List elements are separated by whitespace, so an element is a single
+    // atom (optionally negated); applications must be bracketed.
+    fn list(&mut self) -> Result<list_node::ListNode> {
+        let open_token = self.expect(
+            TokenType::LeftSquareBracket,
+            "Expected '['",
+            "Lists look like: [1 2 3]",
+        )?;
+
+        let mut elements = Vec::new();
+        while !self.current_is(TokenType::RightSquareBracket) {
+            if self.current_token().is_none_or(Self::is_layout) {
+                return Err(Self::error_at(
+                    &open_token,
+                    "Unclosed list",
+                    "Please close the list with ']'",
+                ));
+            }
+            elements.push(self.list_element()?);
+        }
+        self.advance_through_whitespace();
+
+        Ok(list_node::ListNode {
+            elements,
+            span: self.span_from(open_token.span),
+        })
+    }
+
+    fn list_element(&mut self) -> Result<Expr> {
+        let token = self.current_token().unwrap();
+        if let Some((operator, _)) = precedence::prefix_operator(&token.token_type) {
+            let start = token.span;
             self.advance_through_whitespace();
-            let right = self.bin_op_function(func)?;
-            left = Box::new(bin_op_node::BinOpNode {
-                left_node: left,
-                right_node: right,
-                token: operation_token,
-            });
+            if operator == unary_op_node::UnaryOperator::Negate {
+                if let Some(node) = self.negated_minimum(start) {
+                    return Ok(node);
+                }
+            }
+            let node = self.list_element()?;
+            return Ok(Expr::UnaryOp(unary_op_node::UnaryOpNode {
+                span: start.to(node.span()),
+                node: Box::new(node),
+                operator,
+            }));
+        }
+
+        self.atom()
+    }
+
+    // -9223372036854775808 is the only integer whose digits alone do not fit
+    // in 64 bits, so it is read as a single literal when the '-' applies to
+    // the number directly rather than to a power of it.
+    fn negated_minimum(&mut self, minus: Span) -> Option<Expr> {
+        let token = self.current_token()?;
+        if token.token_type != TokenType::Integer
+            || token.value.parse::<i64>().is_ok()
+            || self
+                .next_significant_token()
+                .is_some_and(|next| next.token_type == TokenType::Caret)
+        {
+            return None;
+        }
+
+        let span = minus.to(token.span);
+        self.advance_through_whitespace();
+        Some(Expr::Int(int_node::IntNode {
+            value: i64::MIN,
+            span,
+        }))
+    }
+
+    fn struct_expression(&mut self) -> Result<Expr> {
+        let open_token = self.expect(
+            TokenType::LeftCurlyBracket,
+            "Expected '{'",
+            "Structs look like: { x: 3, y: 4 }",
+        )?;
+
+        if self.current_is(TokenType::FieldId) || self.current_is(TokenType::RightCurlyBracket) {
+            let fields =
+                self.struct_fields(&open_token, "Duplicate field name in struct literal")?;
+            return Ok(Expr::StructLiteral(
+                struct_literal_node::StructLiteralNode {
+                    fields,
+                    span: self.span_from(open_token.span),
+                },
+            ));
+        }
+
+        let base = self.expression()?;
+        self.expect(
+            TokenType::LeftArrow,
+            "Expected '<-' in struct update",
+            "Struct updates look like: { point <- x: 3 }",
+        )?;
+        let fields = self.struct_fields(&open_token, "Duplicate field name in struct update")?;
+        if fields.is_empty() {
+            return Err(Self::error_at(
+                &open_token,
+                "Struct update has no fields",
+                "Struct updates look like: { point <- x: 3 }",
+            ));
         }
 
-        return Ok(left);
+        Ok(Expr::StructUpdate(struct_update_node::StructUpdateNode {
+            base: Box::new(base),
+            fields,
+            span: self.span_from(open_token.span),
+        }))
     }
 
-    // fn unary_op_node(&mut self, types: Vec<TokenType>, func : &str) -> Result<Box<dyn node::Node>> {
-    // }
+    fn struct_fields(
+        &mut self,
+        open_token: &Token,
+        duplicate_error_text: &'static str,
+    ) -> Result<Vec<(Identifier, Expr)>> {
+        let mut fields: Vec<(Identifier, Expr)> = Vec::new();
+        while self.current_is(TokenType::FieldId) {
+            let token = self.current_token().unwrap().clone();
+            let name = Self::field_name(&token);
+            if fields.iter().any(|(field, _)| field.name == name.name) {
+                return Err(Self::error_at(
+                    &token,
+                    duplicate_error_text,
+                    "Each field may only be given once",
+                ));
+            }
+            self.advance_through_whitespace();
+            fields.push((name, self.expression()?));
+
+            if !self.current_is(TokenType::Comma) {
+                break;
+            }
+            self.advance_through_whitespace();
+        }
+
+        if !self.current_is(TokenType::RightCurlyBracket) {
+            return Err(Self::error_at(
+                open_token,
+                "Unclosed struct",
+                "Please close the struct with '}' and separate fields with ','",
+            ));
+        }
+        self.advance_through_whitespace();
+
+        Ok(fields)
+    }
 
-    fn bin_op_function(&mut self, input: &str) -> Result<Box<dyn node::Node>> {
-        if input == "factor" {
-            self.factor()
+    fn function_reference(&mut self) -> Result<Expr> {
+        let start = self.current_span();
+        let namespace = if self.current_is(TokenType::NamespaceId) {
+            let namespace = Self::identifier(self.current_token().unwrap());
+            self.advance_through_whitespace();
+            self.expect(
+                TokenType::Period,
+                "Expected '.' after namespace",
+                "Qualified functions look like: @Namespace.Function",
+            )?;
+            Some(namespace)
         } else {
-            self.term()
+            None
+        };
+
+        if !self.current_is(TokenType::FunctionId) {
+            return Err(self.error(
+                "Expected a function name",
+                "Function names begin with a capital letter",
+            ));
+        }
+        let name = Self::identifier(self.current_token().unwrap());
+        let has_bracketed_arguments = self
+            .tokens
+            .get(self.current_index + 1)
+            .is_some_and(|next| next.token_type == TokenType::LeftBracket);
+        self.advance_through_whitespace();
+
+        let function = Expr::Function(function_node::FunctionNode {
+            namespace,
+            name,
+            span: self.span_from(start),
+        });
+        if !has_bracketed_arguments {
+            return Ok(function);
+        }
+
+        let open_token = self.current_token().unwrap().clone();
+        self.advance_through_whitespace();
+        match self.bracketed_arguments(&open_token) {
+            Ok(arguments) => Ok(Expr::Call(call_node::CallNode {
+                function: Box::new(function),
+                arguments,
+                span: self.span_from(start),
+            })),
+            Err(error) => self.recover_at_closing(error, TokenType::RightBracket, start),
+        }
+    }
+
+    fn bracketed_arguments(&mut self, open_token: &Token) -> Result<Vec<Expr>> {
+        let mut arguments = vec![self.expression()?];
+        while self.current_is(TokenType::Comma) {
+            self.advance_through_whitespace();
+            arguments.push(self.expression()?);
+        }
+        if !self.current_is(TokenType::RightBracket) {
+            return Err(Self::error_at(
+                open_token,
+                "Unclosed bracket",
+                "Please close the function arguments with ')'",
+            ));
+        }
+        self.advance_through_whitespace();
+        Ok(arguments)
+    }
+
+    // Bindings are kept in source order; each binding is in scope for the
+    // bindings after it as well as for the body.
+    fn let_expression(&mut self) -> Result<let_node::LetNode> {
+        let let_token = self.expect(
+            TokenType::KeywordLet,
+            "Expected 'let'",
+            "Let expressions begin with the 'let' keyword",
+        )?;
+        self.expect(
+            TokenType::BlockOpen,
+            "Expected let bindings",
+            "Let expressions look like: let name = expression in body",
+        )?;
+
+        let mut bindings = Vec::new();
+        loop {
+            let pattern = self.irrefutable_pattern()?;
+            // Only parameters may take a list apart, a let binding is
+            // limited to names and struct patterns.
+            if let Some(span) = pattern.list_part() {
+                return Err(SpiralError {
+                    error_text: "List patterns cannot be used in let bindings",
+                    help_text: "Use a match expression to take a list apart",
+                    span,
+                });
+            }
+            self.expect(
+                TokenType::Equals,
+                "Expected '=' in let binding",
+                "Let bindings look like: name = expression",
+            )?;
+            bindings.push((pattern, self.expression()?));
+
+            if !self.current_is(TokenType::BlockSeparator) {
+                break;
+            }
+            self.advance_through_whitespace();
+        }
+
+        self.expect(
+            TokenType::BlockClose,
+            "Unexpected token in let bindings",
+            "Each let binding must begin on a new line, in the same column as the others",
+        )?;
+        self.expect(
+            TokenType::KeywordIn,
+            "Expected 'in' after let bindings",
+            "Let expressions look like: let name = expression in body",
+        )?;
+        let body = self.expression()?;
+
+        Ok(let_node::LetNode {
+            bindings,
+            span: let_token.span.to(body.span()),
+            body: Box::new(body),
+        })
+    }
+
+    // The body of a lambda extends as far as it can, so a lambda passed as
+    // an argument has to be bracketed: Map (\x -> x + 1) list
+    fn lambda_expression(&mut self) -> Result<lambda_node::LambdaNode> {
+        let backslash = self.expect(
+            TokenType::Backslash,
+            "Expected '\\'",
+            "Anonymous functions look like: \\x y -> x + y",
+        )?;
+
+        let mut parameters = Vec::new();
+        while self.starts_pattern() {
+            parameters.push(self.irrefutable_pattern()?);
+        }
+        if parameters.is_empty() {
+            return Err(self.error(
+                "Anonymous function has no parameters",
+                "Anonymous functions look like: \\x y -> x + y",
+            ));
+        }
+
+        self.expect(
+            TokenType::RightArrow,
+            "Expected '->' after the parameters",
+            "Anonymous functions look like: \\x y -> x + y",
+        )?;
+        let body = self.expression()?;
+
+        Ok(lambda_node::LambdaNode {
+            parameters,
+            span: backslash.span.to(body.span()),
+            body: Box::new(body),
+        })
+    }
+
+    fn if_expression(&mut self) -> Result<if_node::IfNode> {
+        let if_token = self.expect(
+            TokenType::KeywordIf,
+            "Expected 'if'",
+            "If expressions begin with the 'if' keyword",
+        )?;
+
+        let mut branches = Vec::new();
+        loop {
+            let condition = self.expression()?;
+            let branch = self.block(
+                "Expected an indented block after the if condition",
+                "Put the value on the next line, indented further than the if",
+            )?;
+            branches.push((condition, branch));
+
+            let is_else_if = self.current_is(TokenType::KeywordElse)
+                && self
+                    .next_significant_token()
+                    .is_some_and(|token| token.token_type == TokenType::KeywordIf);
+            if !is_else_if {
+                break;
+            }
+            self.advance_through_whitespace();
+            self.advance_through_whitespace();
+        }
+
+        if !self.current_is(TokenType::KeywordElse) {
+            return Err(Self::error_at(
+                &if_token,
+                "If expression is missing an else branch",
+                "Every if must have an else since it always has to produce a value",
+            ));
+        }
+        self.advance_through_whitespace();
+        let else_branch = self.branch()?;
+
+        Ok(if_node::IfNode {
+            branches,
+            span: if_token.span.to(else_branch.span()),
+            else_branch: Box::new(else_branch),
+        })
+    }
+
+    fn branch(&mut self) -> Result<Expr> {
+        if self.current_is(TokenType::BlockOpen) {
+            self.block(
+                "Expected an indented block",
+                "Put the branch on the same line or indent it on the lines below",
+            )
+        } else {
+            self.expression()
+        }
+    }
+
+    fn block(&mut self, error_text: &'static str, help_text: &'static str) -> Result<Expr> {
+        self.expect(TokenType::BlockOpen, error_text, help_text)?;
+        let expression = self.expression()?;
+        self.expect(
+            TokenType::BlockClose,
+            "Unexpected token at the end of the block",
+            "A block holds a single expression; indent further to continue the line above",
+        )?;
+        Ok(expression)
+    }
+
+    fn match_expression(&mut self) -> Result<match_node::MatchNode> {
+        let match_token = self.expect(
+            TokenType::KeywordMatch,
+            "Expected 'match'",
+            "Match expressions begin with the 'match' keyword",
+        )?;
+        let subject = self.expression()?;
+
+        let mut arms = Vec::new();
+        while self.current_is(TokenType::KeywordWhen) {
+            self.advance_through_whitespace();
+            let pattern = self.pattern()?;
+            let branch = self.branch()?;
+            arms.push((pattern, branch));
+        }
+
+        let else_branch = if self.current_is(TokenType::KeywordElse) {
+            self.advance_through_whitespace();
+            Some(Box::new(self.branch()?))
+        } else {
+            None
+        };
+
+        if arms.is_empty() && else_branch.is_none() {
+            return Err(Self::error_at(
+                &match_token,
+                "Match expression has no arms",
+                "Add 'when pattern' arms or an 'else' arm to the match",
+            ));
+        }
+
+        Ok(match_node::MatchNode {
+            subject: Box::new(subject),
+            arms,
+            else_branch,
+            span: self.span_from(match_token.span),
+        })
+    }
+
+    fn starts_pattern(&self) -> bool {
+        self.current_token().is_some_and(|token| {
+            matches!(
+                token.token_type,
+                TokenType::Integer
+                    | TokenType::Float
+                    | TokenType::Char
+                    | TokenType::String
+                    | TokenType::KeywordTrue
+                    | TokenType::KeywordFalse
+                    | TokenType::Dash
+                    | TokenType::Underscore
+                    | TokenType::VariableId
+                    | TokenType::LeftCurlyBracket
+                    | TokenType::LeftSquareBracket
+            )
+        })
+    }
+
+    // Parameters and let bindings have no other arm to fall back on, so
+    // their patterns must always match.
+    fn irrefutable_pattern(&mut self) -> Result<Pattern> {
+        let pattern = self.pattern()?;
+        match pattern.refutable_part() {
+            Some(span) => Err(SpiralError {
+                error_text: "This pattern might not match",
+                help_text: "Only patterns that always match can be used here, use a match expression to handle the other cases",
+                span,
+            }),
+            None => Ok(pattern),
+        }
+    }
+
+    fn pattern(&mut self) -> Result<Pattern> {
+        let token = self
+            .current_token()
+            .ok_or_else(|| self.error(
+                "Expected a pattern",
+                "Patterns are names, _, literals, or struct and list patterns like { x: x } and [first ..rest]",
+            ))?
+            .clone();
+
+        match token.token_type {
+            TokenType::Integer
+            | TokenType::Float
+            | TokenType::Char
+            | TokenType::String
+            | TokenType::KeywordTrue
+            | TokenType::KeywordFalse => {
+                let value = Self::literal_value(&token)?;
+                self.advance_through_whitespace();
+                Ok(Pattern::Literal(pattern_node::LiteralPatternNode {
+                    value,
+                    span: token.span,
+                }))
+            }
+            TokenType::Dash => {
+                self.advance_through_whitespace();
+                let number = self.current_token().cloned().filter(|number| {
+                    matches!(number.token_type, TokenType::Integer | TokenType::Float)
+                });
+                let number = number.ok_or_else(|| {
+                    self.error(
+                        "Expected a number after '-' in pattern",
+                        "Only numbers can be negated in a pattern, e.g. when -1",
+                    )
+                })?;
+                self.advance_through_whitespace();
+                // The sign is read along with the digits, which allows
+                // -9223372036854775808 as in an expression.
+                let value = Self::literal_value(&Token {
+                    value: format!("-{}", number.value),
+                    span: token.span.to(number.span),
+                    ..number
+                })?;
+                Ok(Pattern::Literal(pattern_node::LiteralPatternNode {
+                    value,
+                    span: self.span_from(token.span),
+                }))
+            }
+            TokenType::Underscore => {
+                self.advance_through_whitespace();
+                Ok(Pattern::Wildcard(token.span))
+            }
+            TokenType::VariableId => {
+                self.advance_through_whitespace();
+                Ok(Pattern::Binding(Self::identifier(&token)))
+            }
+            TokenType::LeftCurlyBracket => self.struct_pattern(),
+            TokenType::LeftSquareBracket => self.list_pattern(),
+            _ => Err(self.error(
+                "Expected a pattern",
+                "Valid patterns are literals, _, variables, { field: pattern }, [pattern pattern] and [first ..rest]",
+            )),
+        }
+    }
+
+    fn struct_pattern(&mut self) -> Result<Pattern> {
+        let open_token = self.expect(
+            TokenType::LeftCurlyBracket,
+            "Expected '{'",
+            "Struct patterns look like: { x: pattern, y: pattern }",
+        )?;
+
+        let mut fields = Vec::new();
+        loop {
+            let name = self.expect(
+                TokenType::FieldId,
+                "Expected a field name",
+                "Struct patterns look like: { x: pattern, y: pattern }",
+            )?;
+            fields.push((Self::field_name(&name), self.pattern()?));
+
+            if !self.current_is(TokenType::Comma) {
+                break;
+            }
+            self.advance_through_whitespace();
+        }
+
+        self.expect(
+            TokenType::RightCurlyBracket,
+            "Unclosed struct pattern",
+            "Please close the struct pattern with '}'",
+        )?;
+        Ok(Pattern::Struct(pattern_node::StructPatternNode {
+            fields,
+            span: self.span_from(open_token.span),
+        }))
+    }
+
+    fn list_pattern(&mut self) -> Result<Pattern> {
+        let open_token = self.expect(
+            TokenType::LeftSquareBracket,
+            "Expected '['",
+            "List patterns look like: [first second]",
+        )?;
+
+        let mut patterns = Vec::new();
+        let mut rest = None;
+        while !self.current_is(TokenType::RightSquareBracket) {
+            if self.current_token().is_none() {
+                return Err(self.error(
+                    "Unclosed list pattern",
+                    "Please close the list pattern with ']'",
+                ));
+            }
+            if self.current_is(TokenType::DoublePeriod) {
+                self.advance_through_whitespace();
+                rest = Some(Box::new(self.pattern()?));
+                if !self.current_is(TokenType::RightSquareBracket) {
+                    return Err(self.error(
+                        "Expected ']' after the rest of the list",
+                        "The rest of the list must come last, e.g. [first second ..rest]",
+                    ));
+                }
+                break;
+            }
+            patterns.push(self.pattern()?);
+        }
+        self.advance_through_whitespace();
+
+        Ok(Pattern::List(pattern_node::ListPatternNode {
+            patterns,
+            rest,
+            span: self.span_from(open_token.span),
+        }))
+    }
+
+    fn expression(&mut self) -> Result<Expr> {
+        self.expression_with_binding_power(0)
+    }
+
+    fn expression_with_binding_power(&mut self, minimum_binding_power: u8) -> Result<Expr> {
+        let mut left = self.prefix()?;
+
+        while let Some(token) = self.current_token() {
+            let (operator, left_binding_power, right_binding_power) =
+                match precedence::infix_operator(&token.token_type) {
+                    Some(operator) => operator,
+                    None => break,
+                };
+            if left_binding_power < minimum_binding_power {
+                break;
+            }
+
+            self.advance_through_whitespace();
+            let right = self.expression_with_binding_power(right_binding_power)?;
+            left = Expr::BinOp(bin_op_node::BinOpNode {
+                span: left.span().to(right.span()),
+                left_node: Box::new(left),
+                right_node: Box::new(right),
+                operator,
+            });
+        }
+
+        Ok(left)
+    }
+
+    fn prefix(&mut self) -> Result<Expr> {
+        let token = self
+            .current_token()
+            .ok_or_else(|| self.error(
+                "Expected an expression",
+                "Expressions are values like 1, \"text\" or [1 2], names, calls, or let, if and match expressions",
+            ))?;
+
+        match precedence::prefix_operator(&token.token_type) {
+            Some((operator, binding_power)) => {
+                let start = token.span;
+                self.advance_through_whitespace();
+                if operator == unary_op_node::UnaryOperator::Negate {
+                    if let Some(node) = self.negated_minimum(start) {
+                        return Ok(node);
+                    }
+                }
+                let node = self.expression_with_binding_power(binding_power)?;
+                Ok(Expr::UnaryOp(unary_op_node::UnaryOpNode {
+                    span: start.to(node.span()),
+                    node: Box::new(node),
+                    operator,
+                }))
+            }
+            None => self.factor(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::node::Node;
+    use super::{Decl, Expr};
+
+    type FileId = super::super::source_map::FileId;
+    type SourceMap = super::super::source_map::SourceMap;
+
+    #[test]
+    fn module_headers_should_parse_with_specific_exposes() {
+        let result = parse("namespace @Maths exposing [Function variable #Type]").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<ModuleHeaderNode<@Maths,ExposingNode<[Function,variable,#Type]>>>"
+        );
+    }
+
+    #[test]
+    fn module_headers_should_parse_with_wildcard_exposes() {
+        let result = parse("namespace @Maths exposing _").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<ModuleHeaderNode<@Maths,ExposingNode<_>>>"
+        );
+    }
+
+    #[test]
+    fn module_headers_should_parse_with_an_empty_exposing_list() {
+        let result = parse("namespace @Maths exposing []").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<ModuleHeaderNode<@Maths,ExposingNode<[]>>>"
+        );
+    }
+
+    #[test]
+    fn should_raise_error_if_module_header_has_no_exposing_clause() {
+        let result = parse("namespace @Maths");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn should_raise_error_for_unclosed_exposing_list() {
+        let result = parse("namespace @Maths exposing [Function");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn should_raise_error_for_invalid_exposed_item() {
+        let result = parse("namespace @Maths exposing [3]");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn imports_should_parse_with_no_specific_imports() {
+        let result = parse("import @Maths").unwrap();
+        assert_eq!(result, "ProgramNode<ImportNode<@Maths>>");
+    }
+
+    #[test]
+    fn imports_should_parse_with_specific_imports() {
+        let result = parse("import @Maths exposing [Function variable #Type]").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<ImportNode<@Maths,ExposingNode<[Function,variable,#Type]>>>"
+        );
+    }
+
+    #[test]
+    fn imports_should_parse_after_a_module_header() {
+        let result =
+            parse("namespace @Main exposing _\nimport @Maths\nimport @IO exposing _").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<ModuleHeaderNode<@Main,ExposingNode<_>>,ImportNode<@Maths>,ImportNode<@IO,ExposingNode<_>>>"
+        );
+    }
+
+    #[test]
+    fn should_raise_error_for_import_after_a_definition() {
+        let result = parse("import @Maths\nMain = 3 + 4\nimport @IO");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn function_definitions_should_parse_function_definition() {
+        let result = parse("Main = 3 + 4").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[],BinOpNode<IntNode<3>,+,IntNode<4>>>>"
+        );
+    }
+
+    #[test]
+    fn function_definitions_should_parse_parameters() {
+        let result = parse("Add x y = x + y").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Add,[x,y],BinOpNode<VariableNode<x>,+,VariableNode<y>>>>"
+        );
+    }
+
+    #[test]
+    fn function_definitions_should_pair_signatures_with_definitions() {
+        let result = parse("AddTwo : #Fn<#Int,#Int>\nAddTwo x = x + 2").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<SignatureNode<AddTwo,TypeNode<#Fn,TypeNode<#Int>,TypeNode<#Int>>>,AddTwo,[x],BinOpNode<VariableNode<x>,+,IntNode<2>>>>"
+        );
+    }
+
+    #[test]
+    fn programs_should_parse_multiple_declarations() {
+        let result = parse("namespace @Main exposing [Main]\n\nTwo = 2\n\nMain = 3\n").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<ModuleHeaderNode<@Main,ExposingNode<[Main]>>,FunctionDefinitionNode<Two,[],IntNode<2>>,FunctionDefinitionNode<Main,[],IntNode<3>>>"
+        );
+    }
+
+    #[test]
+    fn type_definitions_should_parse_type_definitions() {
+        let result = parse("#Age = #Int").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<TypeDefinitionNode<#Age,[],TypeNode<#Int>>>"
+        );
+    }
+
+    #[test]
+    fn type_definitions_should_parse_unions() {
+        let result = parse("#Number = #Int || #Float").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<TypeDefinitionNode<#Number,[],UnionTypeNode<TypeNode<#Int>,TypeNode<#Float>>>>"
+        );
+    }
+
+    #[test]
+    fn type_definitions_should_parse_struct_types() {
+        let result = parse("#MyStruct = #Struct<x: #Int, y: #Int>").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<TypeDefinitionNode<#MyStruct,[],StructTypeNode<x:TypeNode<#Int>,y:TypeNode<#Int>>>>"
+        );
+    }
+
+    #[test]
+    fn type_definitions_should_parse_type_parameters() {
+        let result = parse("#Pair<a, b> = #Struct<first: a, second: b>").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<TypeDefinitionNode<#Pair,[a,b],StructTypeNode<first:TypeVariableNode<a>,second:TypeVariableNode<b>>>>"
+        );
+    }
+
+    #[test]
+    fn type_expressions_should_parse_nested_generics_and_unions() {
+        let result = parse("Parse : #Fn<#String,#Maybe<#Int || #Float>>\nParse s = 0").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<SignatureNode<Parse,TypeNode<#Fn,TypeNode<#String>,TypeNode<#Maybe,UnionTypeNode<TypeNode<#Int>,TypeNode<#Float>>>>>,Parse,[s],IntNode<0>>>"
+        );
+    }
+
+    #[test]
+    fn let_expressions_should_parse_bindings_and_body() {
+        let result =
+            parse("Main =\n  let\n    twentyFour = 3 * 8\n    sixteen = 4 * 4\n  in\n    twentyFour + sixteen")
+                .unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[],LetNode<[twentyFour=BinOpNode<IntNode<3>,*,IntNode<8>>,sixteen=BinOpNode<IntNode<4>,*,IntNode<4>>],BinOpNode<VariableNode<twentyFour>,+,VariableNode<sixteen>>>>>"
+        );
+    }
+
+    #[test]
+    fn let_expressions_should_allow_bindings_to_reference_earlier_bindings() {
+        let result = parse("Main = let a = 1\n           b = a + 1\n       in b").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[],LetNode<[a=IntNode<1>,b=BinOpNode<VariableNode<a>,+,IntNode<1>>],VariableNode<b>>>>"
+        );
+    }
+
+    #[test]
+    fn let_expressions_should_nest_in_multi_line_bindings() {
+        let expected = "ProgramNode<FunctionDefinitionNode<Main,[],LetNode<[a=LetNode<[b=IntNode<1>],VariableNode<b>>,c=IntNode<2>],BinOpNode<VariableNode<a>,+,VariableNode<c>>>>>";
+        let result = parse(
+            "Main =\n  let\n    a =\n      let\n        b = 1\n      in b\n    c = 2\n  in a + c",
+        )
+        .unwrap();
+        assert_eq!(result, expected);
+        let result = parse("Main =\n  let\n    a = let b = 1 in b\n    c = 2\n  in a + c").unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn should_raise_error_for_let_without_in() {
+        let result = parse("Main = let a = 1");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn should_raise_error_for_let_without_bindings() {
+        let result = parse("Main = let in 3");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn if_expressions_should_parse_without_else_if_clause() {
+        let result = parse("Main key n =\n  if key\n    n + 1\n  else\n    n").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[key,n],IfNode<[VariableNode<key>=>BinOpNode<VariableNode<n>,+,IntNode<1>>],VariableNode<n>>>>"
+        );
+    }
+
+    #[test]
+    fn if_expressions_should_parse_with_an_else_if_clause() {
+        let result =
+            parse("Main up down n =\n  if up\n    n + 1\n  else if down\n    n - 1\n  else\n    n")
+                .unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[up,down,n],IfNode<[VariableNode<up>=>BinOpNode<VariableNode<n>,+,IntNode<1>>,VariableNode<down>=>BinOpNode<VariableNode<n>,-,IntNode<1>>],VariableNode<n>>>>"
+        );
+    }
+
+    #[test]
+    fn should_raise_error_for_if_without_else() {
+        let result = parse("Main key n =\n  if key\n    n + 1");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn should_underline_unclosed_brackets_up_to_the_last_token() {
+        let error = parse("Main = (1 + 2").unwrap_err();
+        assert_eq!(error.error_text, "Unclosed bracket");
+        assert_eq!((error.span.start, error.span.end), (7, 13));
+    }
+
+    #[test]
+    fn should_point_at_the_if_keyword_when_else_is_missing() {
+        let input = "Main key n =\n  if key\n    n + 1";
+        let error = parse(input).unwrap_err();
+        assert_eq!((error.span.start, error.span.end), (15, 17));
+
+        let mut source_map = SourceMap::new();
+        let file_id = source_map.add("main.spiral", input);
+        assert_eq!(
+            source_map.line_number(file_id, error.span.start as usize),
+            2
+        );
+    }
+
+    #[test]
+    fn should_raise_error_for_else_if_without_else() {
+        let result = parse("Main a b =\n  if a\n    1\n  else if b\n    2");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn match_expressions_should_parse_without_a_when_clause() {
+        let result = parse("Main n =\n  match n\n  else\n    1").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[n],MatchNode<VariableNode<n>,[],IntNode<1>>>>"
+        );
+    }
+
+    #[test]
+    fn match_expressions_should_parse_when_clauses() {
+        let result =
+            parse("Fib n =\n  match n\n  when 0\n    1\n  when 1\n    1\n  else\n    n - 1")
+                .unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Fib,[n],MatchNode<VariableNode<n>,[LiteralPatternNode<0>=>IntNode<1>,LiteralPatternNode<1>=>IntNode<1>],BinOpNode<VariableNode<n>,-,IntNode<1>>>>>"
+        );
+    }
+
+    #[test]
+    fn match_expressions_should_parse_literal_wildcard_and_binding_patterns() {
+        let result = parse(
+            "Main x =\n  match x\n  when 'a'\n    1\n  when \"b\"\n    2\n  when true\n    3\n  when -4\n    4\n  when y\n    y\n  when _\n    0",
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[x],MatchNode<VariableNode<x>,[LiteralPatternNode<a>=>IntNode<1>,LiteralPatternNode<b>=>IntNode<2>,LiteralPatternNode<true>=>IntNode<3>,LiteralPatternNode<-4>=>IntNode<4>,BindingPatternNode<y>=>VariableNode<y>,WildcardPatternNode=>IntNode<0>]>>>"
+        );
+    }
+
+    #[test]
+    fn match_expressions_should_parse_struct_and_list_patterns() {
+        let result = parse(
+            "Main p =\n  match p\n  when { x: 0, y: y }\n    y\n  when [a _ 3]\n    a\n  when []\n    0",
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[p],MatchNode<VariableNode<p>,[StructPatternNode<x:LiteralPatternNode<0>,y:BindingPatternNode<y>>=>VariableNode<y>,ListPatternNode<BindingPatternNode<a>,WildcardPatternNode,LiteralPatternNode<3>>=>VariableNode<a>,ListPatternNode<>=>IntNode<0>]>>>"
+        );
+    }
+
+    #[test]
+    fn should_raise_error_for_match_without_arms() {
+        let result = parse("Main n =\n  match n");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn should_raise_error_for_invalid_pattern() {
+        let result = parse("Main n =\n  match n\n  when +\n    1");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn calls_should_parse_juxtaposition_application() {
+        let result = parse("Main = AddTwo 3").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[],CallNode<FunctionNode<AddTwo>,IntNode<3>>>>"
+        );
+    }
+
+    #[test]
+    fn calls_should_parse_namespace_qualified_calls() {
+        let result = parse("Main = @IO.Print(AddTwo 3)").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[],CallNode<FunctionNode<@IO.Print>,CallNode<FunctionNode<AddTwo>,IntNode<3>>>>>"
+        );
+    }
+
+    #[test]
+    fn calls_should_parse_bracketed_arguments() {
+        let result = parse("Fib n = Fib(n-1) + Fib(n-2)").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Fib,[n],BinOpNode<CallNode<FunctionNode<Fib>,BinOpNode<VariableNode<n>,-,IntNode<1>>>,+,CallNode<FunctionNode<Fib>,BinOpNode<VariableNode<n>,-,IntNode<2>>>>>>"
+        );
+    }
+
+    #[test]
+    fn calls_should_parse_multiple_arguments() {
+        let result = parse("Main = Add(1, 2) + Add 3 (4 * 5) x @Maths.Pi").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[],BinOpNode<CallNode<FunctionNode<Add>,IntNode<1>,IntNode<2>>,+,CallNode<FunctionNode<Add>,IntNode<3>,BinOpNode<IntNode<4>,*,IntNode<5>>,VariableNode<x>,FunctionNode<@Maths.Pi>>>>>"
+        );
+    }
+
+    #[test]
+    fn calls_should_accept_multi_line_conditionals_as_arguments() {
+        let result = parse("Main x = @IO.Print(match x\n  when 1\n    2\n  else\n    3)").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[x],CallNode<FunctionNode<@IO.Print>,MatchNode<VariableNode<x>,[LiteralPatternNode<1>=>IntNode<2>],IntNode<3>>>>>"
+        );
+        let result = parse("Main x = F x (if x\n  1\nelse\n  2)").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[x],CallNode<FunctionNode<F>,VariableNode<x>,IfNode<[VariableNode<x>=>IntNode<1>],IntNode<2>>>>>"
+        );
+    }
+
+    #[test]
+    fn calls_should_bind_tighter_than_operators() {
+        let result = parse("Main x = f x + 1").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[x],BinOpNode<CallNode<VariableNode<f>,VariableNode<x>>,+,IntNode<1>>>>"
+        );
+    }
+
+    #[test]
+    fn calls_should_not_take_arguments_from_the_next_line() {
+        let result = parse("One = Id 1\nTwo = 2").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<One,[],CallNode<FunctionNode<Id>,IntNode<1>>>,FunctionDefinitionNode<Two,[],IntNode<2>>>"
+        );
+    }
+
+    #[test]
+    fn should_raise_error_for_namespace_without_function() {
+        let result = parse("Main = @IO.x");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn should_raise_error_for_unclosed_call_arguments() {
+        let result = parse("Main = Add(1, 2");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn bin_ops_should_respect_math_precedence() {
+        let result = parse("Main = 1 + 2 - 3 * 4 / 5 ^ 6").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[],BinOpNode<BinOpNode<IntNode<1>,+,IntNode<2>>,-,BinOpNode<BinOpNode<IntNode<3>,*,IntNode<4>>,/,BinOpNode<IntNode<5>,^,IntNode<6>>>>>>"
+        );
+    }
+
+    #[test]
+    fn bin_ops_should_parse_caret_as_right_associative() {
+        let result = parse("Main = 2 ^ 3 ^ 2").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[],BinOpNode<IntNode<2>,^,BinOpNode<IntNode<3>,^,IntNode<2>>>>>"
+        );
+    }
+
+    #[test]
+    fn bin_ops_should_parse_unary_minus_below_caret() {
+        let result = parse("Main = -2 ^ 2 * -3").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[],BinOpNode<UnaryOpNode<-,BinOpNode<IntNode<2>,^,IntNode<2>>>,*,UnaryOpNode<-,IntNode<3>>>>>"
+        );
+    }
+
+    #[test]
+    fn bin_ops_should_parse_boolean_operators() {
+        let result = parse("Main a b c = !a || b && c").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[a,b,c],BinOpNode<UnaryOpNode<!,VariableNode<a>>,||,BinOpNode<VariableNode<b>,&&,VariableNode<c>>>>>"
+        );
+    }
+
+    #[test]
+    fn bin_ops_should_parse_comparisons_below_arithmetic() {
+        let result = parse("Main a b = a + 1 >= b * 2 && a != b").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[a,b],BinOpNode<BinOpNode<BinOpNode<VariableNode<a>,+,IntNode<1>>,>=,BinOpNode<VariableNode<b>,*,IntNode<2>>>,&&,BinOpNode<VariableNode<a>,!=,VariableNode<b>>>>>"
+        );
+    }
+
+    #[test]
+    fn bin_ops_should_parse_concatenation_as_right_associative() {
+        let result = parse("Main a b c = a ++ b ++ c").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[a,b,c],BinOpNode<VariableNode<a>,++,BinOpNode<VariableNode<b>,++,VariableNode<c>>>>>"
+        );
+    }
+
+    #[test]
+    fn bin_ops_should_parse_flow_left_to_right_with_lowest_precedence() {
+        let result = parse("Main x = x + 1 |> Double |> @IO.Print").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[x],BinOpNode<BinOpNode<BinOpNode<VariableNode<x>,+,IntNode<1>>,|>,FunctionNode<Double>>,|>,FunctionNode<@IO.Print>>>>"
+        );
+    }
+
+    #[test]
+    fn should_raise_error_for_missing_right_operand() {
+        let result = parse("Main = 1 +");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn literals_should_parse_floats() {
+        let result = parse("Main = 3.14 + 4.9").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[],BinOpNode<FloatNode<3.14>,+,FloatNode<4.9>>>>"
+        );
+    }
+
+    #[test]
+    fn literals_should_parse_characters_and_strings() {
+        let result = parse("Main = 'a' ++ \"hello\"").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[],BinOpNode<CharNode<a>,++,StringNode<hello>>>>"
+        );
+    }
+
+    #[test]
+    fn literals_should_parse_booleans() {
+        let result = parse("Main = true || false").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[],BinOpNode<BoolNode<true>,||,BoolNode<false>>>>"
+        );
+    }
+
+    #[test]
+    fn literals_should_parse_lists() {
+        let result = parse("Main x = [1 -2 x (Double x) [] [3.5]]").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[x],ListNode<IntNode<1>,UnaryOpNode<-,IntNode<2>>,VariableNode<x>,CallNode<FunctionNode<Double>,VariableNode<x>>,ListNode<>,ListNode<FloatNode<3.5>>>>>"
+        );
+    }
+
+    #[test]
+    fn literals_should_be_usable_as_arguments() {
+        let result = parse("Main = @IO.Print \"Hello\" [1 2] 'c' true").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[],CallNode<FunctionNode<@IO.Print>,StringNode<Hello>,ListNode<IntNode<1>,IntNode<2>>,CharNode<c>,BoolNode<true>>>>"
+        );
+    }
+
+    #[test]
+    fn should_raise_error_for_char_with_multiple_characters() {
+        let result = parse("Main = 'ab'");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn should_raise_error_for_integer_overflow() {
+        let result = parse("Main = 99999999999999999999");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn should_raise_error_for_unclosed_list() {
+        let result = parse("Main = [1 2");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn structs_should_parse_struct_creation() {
+        let result = parse("Main = { x: 3, y: 4 }").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[],StructLiteralNode<x:IntNode<3>,y:IntNode<4>>>>"
+        );
+    }
+
+    #[test]
+    fn structs_should_parse_field_access() {
+        let result = parse("Main point = point.x == 3").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[point],BinOpNode<FieldAccessNode<VariableNode<point>,x>,==,IntNode<3>>>>"
+        );
+    }
+
+    #[test]
+    fn structs_should_parse_chained_field_access() {
+        let result = parse("Main a = Length a.b.c (Get a).d").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[a],CallNode<FunctionNode<Length>,FieldAccessNode<FieldAccessNode<VariableNode<a>,b>,c>,FieldAccessNode<CallNode<FunctionNode<Get>,VariableNode<a>>,d>>>>"
+        );
+    }
+
+    #[test]
+    fn structs_should_parse_struct_editing() {
+        let result = parse("Move point = { point <- x: point.x + 1, y: point.y + 1 }").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Move,[point],StructUpdateNode<VariableNode<point>,x:BinOpNode<FieldAccessNode<VariableNode<point>,x>,+,IntNode<1>>,y:BinOpNode<FieldAccessNode<VariableNode<point>,y>,+,IntNode<1>>>>>"
+        );
+    }
+
+    #[test]
+    fn should_raise_error_for_duplicate_struct_literal_fields() {
+        let result = parse("Main = { x: 3, x: 4 }");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn should_raise_error_for_duplicate_struct_update_fields() {
+        let result = parse("Move point = { point <- x: 1, x: 2 }");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn should_raise_error_for_unclosed_struct() {
+        let result = parse("Main = { x: 3, y: 4");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn should_parse_arguments_on_continuation_lines() {
+        let result = parse("Main = @IO.Print\n  \"Hello World!\"").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[],CallNode<FunctionNode<@IO.Print>,StringNode<Hello World!>>>>"
+        );
+    }
+
+    #[test]
+    fn should_parse_operators_on_continuation_lines() {
+        let result = parse("Main x =\n  x\n    |> Double\n    |> @IO.Print\nNext = 1").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[x],BinOpNode<BinOpNode<VariableNode<x>,|>,FunctionNode<Double>>,|>,FunctionNode<@IO.Print>>>,FunctionDefinitionNode<Next,[],IntNode<1>>>"
+        );
+    }
+
+    #[test]
+    fn should_parse_nested_blocks() {
+        let result = parse(
+            "Main a b =\n  if a\n    let\n      x =\n        if b\n          1\n        else\n          2\n    in\n      x\n  else\n    0",
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[a,b],IfNode<[VariableNode<a>=>LetNode<[x=IfNode<[VariableNode<b>=>IntNode<1>],IntNode<2>>],VariableNode<x>>],IntNode<0>>>>"
+        );
+    }
+
+    #[test]
+    fn should_raise_error_for_multiple_expressions_in_a_block() {
+        let result = parse("Main a =\n  if a\n    1\n  else\n    2\n   3");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn should_raise_error_for_duplicate_struct_type_fields() {
+        let result = parse("#Point = #Struct<x: #Int, x: #Int>");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn should_raise_error_for_unclosed_type_arguments() {
+        let result = parse("#Numbers = #List<#Int");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn should_raise_error_for_orphaned_signature() {
+        let result = parse("AddTwo : #Fn<#Int,#Int>\nMain = 3");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn should_raise_error_for_signature_at_end_of_file() {
+        let result = parse("Main = 3\nAddTwo : #Fn<#Int,#Int>");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn should_raise_error_for_declarations_on_the_same_line() {
+        let result = parse("One = 1 Two = 2");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn lambdas_should_parse() {
+        let result = parse("Main = \\x y -> x + y").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[],LambdaNode<[x,y],BinOpNode<VariableNode<x>,+,VariableNode<y>>>>>"
+        );
+    }
+
+    #[test]
+    fn lambdas_should_parse_as_bracketed_arguments() {
+        let result = parse("Main list = Map (\\x -> x * 2) list").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[list],CallNode<FunctionNode<Map>,LambdaNode<[x],BinOpNode<VariableNode<x>,*,IntNode<2>>>,VariableNode<list>>>>"
+        );
+    }
+
+    #[test]
+    fn lambda_bodies_should_continue_onto_indented_lines() {
+        let result = parse("Main = \\x ->\n  if x\n    1\n  else\n    2").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[],LambdaNode<[x],IfNode<[VariableNode<x>=>IntNode<1>],IntNode<2>>>>>"
+        );
+    }
+
+    #[test]
+    fn should_raise_error_for_lambda_without_parameters() {
+        let result = parse("Main = \\ -> 1");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn should_raise_error_for_lambda_without_arrow() {
+        let result = parse("Main = \\x x + 1");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn partially_applied_functions_should_parse_in_flows() {
+        let result = parse("Main xs = xs |> Map (Add 1) |> Add 2").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[xs],BinOpNode<BinOpNode<VariableNode<xs>,|>,CallNode<FunctionNode<Map>,CallNode<FunctionNode<Add>,IntNode<1>>>>,|>,CallNode<FunctionNode<Add>,IntNode<2>>>>>"
+        );
+    }
+
+    #[test]
+    fn let_bindings_should_destructure_structs() {
+        let result =
+            parse("Main point = let { x: px, y: py } = point\n             in px + py").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[point],LetNode<[StructPatternNode<x:BindingPatternNode<px>,y:BindingPatternNode<py>>=VariableNode<point>],BinOpNode<VariableNode<px>,+,VariableNode<py>>>>>"
+        );
+    }
+
+    #[test]
+    fn function_parameters_should_destructure() {
+        let result = parse("Length { x: x, y: _ } [..rest] = x").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Length,[StructPatternNode<x:BindingPatternNode<x>,y:WildcardPatternNode>,ListPatternNode<..BindingPatternNode<rest>>],VariableNode<x>>>"
+        );
+    }
+
+    #[test]
+    fn lambda_parameters_should_destructure() {
+        let result = parse("Main = \\{ x: x } _ -> x").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[],LambdaNode<[StructPatternNode<x:BindingPatternNode<x>>,WildcardPatternNode],VariableNode<x>>>>"
+        );
+    }
+
+    #[test]
+    fn match_expressions_should_parse_head_and_tail_patterns() {
+        let result = parse(
+            "Sum xs =\n  match xs\n  when [first ..rest]\n    first + Sum rest\n  when []\n    0",
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Sum,[xs],MatchNode<VariableNode<xs>,[ListPatternNode<BindingPatternNode<first>,..BindingPatternNode<rest>>=>BinOpNode<VariableNode<first>,+,CallNode<FunctionNode<Sum>,VariableNode<rest>>>,ListPatternNode<>=>IntNode<0>]>>>"
+        );
+    }
+
+    #[test]
+    fn should_raise_error_if_the_rest_of_a_list_pattern_is_not_last() {
+        let result = parse("Main xs =\n  match xs\n  when [..rest last]\n    last");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn integers_should_range_down_to_the_negated_minimum() {
+        let result = parse("Main x = -9223372036854775808 + [-9223372036854775808]").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[x],BinOpNode<IntNode<-9223372036854775808>,+,ListNode<IntNode<-9223372036854775808>>>>>"
+        );
+
+        let result =
+            parse("Main x =\n  match x\n  when -9223372036854775808\n    1\n  else\n    2")
+                .unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[x],MatchNode<VariableNode<x>,[LiteralPatternNode<-9223372036854775808>=>IntNode<1>],IntNode<2>>>>"
+        );
+    }
+
+    #[test]
+    fn should_raise_error_for_integers_outside_of_64_bits() {
+        for input in [
+            "Main = 9223372036854775808",
+            "Main = -(9223372036854775808)",
+            "Main = -9223372036854775808 ^ 2",
+            "Main x =\n  match x\n  when 9223372036854775808\n    1\n  else\n    2",
+        ] {
+            let error = parse(input).unwrap_err();
+            assert_eq!(
+                error.error_text, "Integer literal is too large",
+                "{}",
+                input
+            );
+        }
+        assert_eq!(parse("Main = -9223372036854775809").is_err(), true);
+    }
+
+    #[test]
+    fn should_raise_error_for_refutable_let_bindings() {
+        let error = parse("Main p = let { x: 0 } = p in 1").unwrap_err();
+        assert_eq!(error.error_text, "This pattern might not match");
+        assert_eq!((error.span.start, error.span.end), (18, 19));
+    }
+
+    #[test]
+    fn function_parameters_should_destructure_lists() {
+        let result = parse("Head [first ..rest] = first").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Head,[ListPatternNode<BindingPatternNode<first>,..BindingPatternNode<rest>>],VariableNode<first>>>"
+        );
+
+        let result = parse("Main [a b] = a").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[ListPatternNode<BindingPatternNode<a>,BindingPatternNode<b>>],VariableNode<a>>>"
+        );
+    }
+
+    #[test]
+    fn should_raise_error_for_list_patterns_in_let_bindings() {
+        let error = parse("Main = let [a b] = [1 2 3] in a").unwrap_err();
+        assert_eq!(
+            error.error_text,
+            "List patterns cannot be used in let bindings"
+        );
+        assert_eq!((error.span.start, error.span.end), (11, 16));
+
+        let result = parse("Main p = let { xs: [..rest] } = p in rest");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn should_raise_error_for_refutable_parameters() {
+        let error = parse("Main [a 0] = a").unwrap_err();
+        assert_eq!(error.error_text, "This pattern might not match");
+        assert_eq!((error.span.start, error.span.end), (8, 9));
+    }
+
+    #[test]
+    fn doc_comments_after_a_signature_should_document_the_function() {
+        let program = parse_program("AddTwo : #Int\n--| doc\nAddTwo = 1")
+            .unwrap()
+            .0;
+        match &program.declarations[0] {
+            Decl::Function(definition) => {
+                assert_eq!(definition.documentation.as_deref(), Some("doc"))
+            }
+            _ => panic!("expected a function definition"),
+        }
+
+        let program = parse_program("--| Adds two\nAddTwo : #Int\n--| to a number\nAddTwo = 1")
+            .unwrap()
+            .0;
+        match &program.declarations[0] {
+            Decl::Function(definition) => assert_eq!(
+                definition.documentation.as_deref(),
+                Some("Adds two\nto a number")
+            ),
+            _ => panic!("expected a function definition"),
+        }
+    }
+
+    #[test]
+    fn should_raise_error_for_doc_comments_trailing_a_declaration() {
+        let result = parse("One = 1 --| One\nTwo = 2");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn doc_comments_should_attach_to_the_following_declaration() {
+        let program = parse_program(
+            "--| The first\nOne = 1\n\n--| Adds two\n--| to a number\nAddTwo : #Fn<#Int,#Int>\nAddTwo n = n + 2\nPlain = 3\n--| A point\n#Point = #Struct<x: #Int, y: #Int>",
+        )
+        .unwrap()
+        .0;
+        let documentation: Vec<Option<&str>> = program
+            .declarations
+            .iter()
+            .map(|declaration| match declaration {
+                Decl::Function(definition) => definition.documentation.as_deref(),
+                Decl::Type(definition) => definition.documentation.as_deref(),
+                Decl::Error(_) => panic!("unexpected error node"),
+            })
+            .collect();
+        assert_eq!(
+            documentation,
+            vec![
+                Some("The first"),
+                Some("Adds two\nto a number"),
+                None,
+                Some("A point")
+            ]
+        );
+    }
+
+    #[test]
+    fn comments_should_be_ignored_inside_expressions() {
+        let result = parse(
+            "Main =\n  let\n    -- a comment\n    a = 1 {- inline -} + 2\n    --| not documentation\n    b = 3\n  in\n    a + b",
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[],LetNode<[a=BinOpNode<IntNode<1>,+,IntNode<2>>,b=IntNode<3>],BinOpNode<VariableNode<a>,+,VariableNode<b>>>>>"
+        );
+    }
+
+    #[test]
+    fn literals_should_carry_the_value_of_hex_binary_and_separated_numbers() {
+        let result = parse("Main = [0xFF 0b1010 1_000 2.5e2]").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[],ListNode<IntNode<255>,IntNode<10>,IntNode<1000>,FloatNode<250>>>>"
+        );
+    }
+
+    #[test]
+    fn interpolated_strings_should_desugar_to_concatenation() {
+        let result = parse("Main name age = \"Hello {name}, you are {age + 1}\"").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[name,age],BinOpNode<StringNode<Hello >,++,BinOpNode<CallNode<FunctionNode<@String.From>,VariableNode<name>>,++,BinOpNode<StringNode<, you are >,++,CallNode<FunctionNode<@String.From>,BinOpNode<VariableNode<age>,+,IntNode<1>>>>>>>>"
+        );
+    }
+
+    #[test]
+    fn interpolated_strings_should_match_hand_written_concatenation() {
+        let interpolated = parse("Main x = \"a{x}b\"").unwrap();
+        let hand_written = parse("Main x = \"a\" ++ @String.From(x) ++ \"b\"").unwrap();
+        assert_eq!(interpolated, hand_written);
+    }
+
+    #[test]
+    fn interpolated_strings_should_not_add_empty_segments() {
+        let result = parse("Main n = \"{n}\" ++ \"{n}{n}\"").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Main,[n],BinOpNode<CallNode<FunctionNode<@String.From>,VariableNode<n>>,++,BinOpNode<CallNode<FunctionNode<@String.From>,VariableNode<n>>,++,CallNode<FunctionNode<@String.From>,VariableNode<n>>>>>>"
+        );
+    }
+
+    #[test]
+    fn should_raise_error_for_empty_interpolations() {
+        let result = parse("Main = \"Hello {}\"");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn multi_line_strings_should_not_disturb_layout() {
+        let result = parse(
+            "Query =\n  \"\"\"\n    SELECT *\n      FROM users\n    \"\"\"\nOther = r\"\\d\"",
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Query,[],StringNode<SELECT *\n  FROM users>>,FunctionDefinitionNode<Other,[],StringNode<\\d>>>"
+        );
+    }
+
+    #[test]
+    fn function_definitions_should_parse_unicode_names() {
+        let result = parse("Größe ärger = ärger").unwrap();
+        assert_eq!(
+            result,
+            "ProgramNode<FunctionDefinitionNode<Größe,[ärger],VariableNode<ärger>>>"
+        );
+    }
+
+    #[test]
+    fn nodes_should_carry_the_span_of_their_source() {
+        let program = parse_program("Main = 1 + foo.bar").unwrap().0;
+        let Decl::Function(definition) = &program.declarations[0] else {
+            panic!("expected a function definition");
+        };
+        assert_eq!((definition.span.start, definition.span.end), (0, 18));
+
+        let Expr::BinOp(bin_op) = &definition.body else {
+            panic!("expected a binary operation");
+        };
+        assert_eq!(bin_op.operator, super::bin_op_node::BinaryOperator::Add);
+        assert_eq!((bin_op.span.start, bin_op.span.end), (7, 18));
+        assert_eq!(
+            (bin_op.left_node.span().start, bin_op.left_node.span().end),
+            (7, 8)
+        );
+
+        let Expr::FieldAccess(field_access) = bin_op.right_node.as_ref() else {
+            panic!("expected a field access");
+        };
+        assert_eq!(field_access.field.name, "bar");
+        assert_eq!((field_access.span.start, field_access.span.end), (11, 18));
+    }
+
+    #[test]
+    fn spans_should_cover_multi_line_expressions() {
+        let program =
+            parse_program("Main n =\n  match n\n  when 0\n    1\n  else\n    2\nOther = 3")
+                .unwrap()
+                .0;
+        let Decl::Function(definition) = &program.declarations[0] else {
+            panic!("expected a function definition");
+        };
+        assert_eq!(
+            (definition.body.span().start, definition.body.span().end),
+            (11, 46)
+        );
+    }
+
+    #[test]
+    fn field_names_should_not_include_the_colon() {
+        let program = parse_program("Main = { x: 1 }").unwrap().0;
+        let Decl::Function(definition) = &program.declarations[0] else {
+            panic!("expected a function definition");
+        };
+        let Expr::StructLiteral(literal) = &definition.body else {
+            panic!("expected a struct literal");
+        };
+        assert_eq!(literal.fields[0].0.name, "x");
+        assert_eq!(
+            (literal.fields[0].0.span.start, literal.fields[0].0.span.end),
+            (9, 10)
+        );
+    }
+
+    #[test]
+    fn should_report_errors_in_every_declaration() {
+        let input = "Main = 1 +\nOther = 2\nBroken = )\nLast = 3";
+        let (program, errors) = parse_program(input).unwrap();
+        assert_eq!(
+            program.represent(),
+            "ProgramNode<ErrorNode,FunctionDefinitionNode<Other,[],IntNode<2>>,ErrorNode,FunctionDefinitionNode<Last,[],IntNode<3>>>"
+        );
+
+        let mut source_map = SourceMap::new();
+        let file_id = source_map.add("main.spiral", input);
+        assert_eq!(
+            errors
+                .iter()
+                .map(|error| source_map.line_number(file_id, error.span.start as usize))
+                .collect::<Vec<usize>>(),
+            vec![1, 3]
+        );
+        assert_eq!((errors[0].span.start, errors[0].span.end), (10, 10));
+    }
+
+    #[test]
+    fn should_point_after_the_last_token_when_a_line_ends_early() {
+        let (_, errors) = parse_program("Main =\nTwo = 2").unwrap();
+        assert_eq!(errors[0].error_text, "Unknown Factor");
+        assert_eq!((errors[0].span.start, errors[0].span.end), (6, 6));
+
+        let (_, errors) = parse_program("Main = 1 *").unwrap();
+        assert_eq!((errors[0].span.start, errors[0].span.end), (10, 10));
+    }
+
+    #[test]
+    fn should_recover_at_closing_brackets() {
+        let (program, errors) = parse_program("Main = (1 + ) * [2 +] + Add(3, ) + 4").unwrap();
+        assert_eq!(
+            program.represent(),
+            "ProgramNode<FunctionDefinitionNode<Main,[],BinOpNode<BinOpNode<BinOpNode<ErrorNode,*,ErrorNode>,+,ErrorNode>,+,IntNode<4>>>>"
+        );
+        assert_eq!(errors.len(), 3);
+    }
+
+    #[test]
+    fn should_recover_from_brackets_left_open_at_the_end_of_a_line() {
+        let input = "Main = F(1\nTwo = 2\nThree = 3 +";
+        let (program, errors) = parse_program(input).unwrap();
+        assert_eq!(
+            program.represent(),
+            "ProgramNode<ErrorNode,FunctionDefinitionNode<Two,[],IntNode<2>>,ErrorNode>"
+        );
+        let error_texts: Vec<&str> = errors.iter().map(|error| error.error_text).collect();
+        assert_eq!(
+            error_texts,
+            vec!["Unclosed bracket", "Expected an expression"]
+        );
+        assert_eq!((errors[0].span.start, errors[0].span.end), (8, 9));
+
+        let input = "A = [1 2\nB = 1 +\nC = { x: 1, x: 2 }";
+        let (program, errors) = parse_program(input).unwrap();
+        assert_eq!(
+            program.represent(),
+            "ProgramNode<ErrorNode,ErrorNode,FunctionDefinitionNode<C,[],ErrorNode>>"
+        );
+        let error_texts: Vec<&str> = errors.iter().map(|error| error.error_text).collect();
+        assert_eq!(
+            error_texts,
+            vec![
+                "Unclosed list",
+                "Unknown Factor",
+                "Duplicate field name in struct literal"
+            ]
+        );
+        assert_eq!((errors[0].span.start, errors[0].span.end), (4, 5));
+
+        let mut source_map = SourceMap::new();
+        let file_id = source_map.add("main.spiral", input);
+        assert_eq!(
+            errors
+                .iter()
+                .map(|error| source_map.line_number(file_id, error.span.start as usize))
+                .collect::<Vec<usize>>(),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn should_not_stop_at_let_bindings_when_recovering() {
+        let (program, errors) =
+            parse_program("Main = let a = )\n           b = 2\n       in b\nOther = 1").unwrap();
+        assert_eq!(
+            program.represent(),
+            "ProgramNode<ErrorNode,FunctionDefinitionNode<Other,[],IntNode<1>>>"
+        );
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn should_recover_from_unexpected_tokens_after_a_declaration() {
+        let (program, errors) = parse_program("Main = 1 2 3\nOther = 2").unwrap();
+        assert_eq!(
+            program.represent(),
+            "ProgramNode<FunctionDefinitionNode<Main,[],IntNode<1>>,FunctionDefinitionNode<Other,[],IntNode<2>>>"
+        );
+        assert_eq!(errors.len(), 1);
+    }
+
+    fn parse(input: &str) -> Result<String, super::SpiralError<'static>> {
+        let (program, mut errors) = parse_program(input)?;
+        if !errors.is_empty() {
+            return Err(errors.remove(0));
         }
+        Ok(program.represent())
     }
 
-    fn expression(&mut self) -> Result<Box<dyn node::Node>> {
-        self.bin_op_node(vec![TokenType::Plus, TokenType::Dash], "term")
+    fn parse_program(
+        input: &str,
+    ) -> Result<
+        (
+            super::program_node::ProgramNode,
+            Vec<super::SpiralError<'static>>,
+        ),
+        super::SpiralError<'static>,
+    > {
+        let mut t = super::super::tokenizer::Tokenizer::build(FileId::default(), input);
+        let tokens = t.execute()?;
+        let mut l = super::super::layout::Layout::build(input, tokens);
+        let tokens = l.execute()?;
+        let mut p = super::Parser::build(tokens);
+        Ok(p.execute())
     }
 }
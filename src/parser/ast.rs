@@ -0,0 +1,207 @@
+use super::node::Node;
+use std::fmt;
+
+type Span = super::super::span::Span;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Identifier {
+    pub name: String,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    Int(i64),
+    Float(f64),
+    Char(char),
+    String(String),
+    Bool(bool),
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Literal::Int(value) => write!(f, "{}", value),
+            Literal::Float(value) => write!(f, "{}", value),
+            Literal::Char(value) => write!(f, "{}", value),
+            Literal::String(value) => write!(f, "{}", value),
+            Literal::Bool(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Decl {
+    Function(super::function_definition_node::FunctionDefinitionNode),
+    Type(super::type_definition_node::TypeDefinitionNode),
+    Error(Span),
+}
+
+impl Node for Decl {
+    fn represent(&self) -> String {
+        match self {
+            Decl::Function(node) => node.represent(),
+            Decl::Type(node) => node.represent(),
+            Decl::Error(_) => "ErrorNode".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Int(super::int_node::IntNode),
+    Float(super::float_node::FloatNode),
+    Char(super::char_node::CharNode),
+    String(super::string_node::StringNode),
+    Bool(super::bool_node::BoolNode),
+    List(super::list_node::ListNode),
+    Variable(super::variable_node::VariableNode),
+    Function(super::function_node::FunctionNode),
+    Call(super::call_node::CallNode),
+    BinOp(super::bin_op_node::BinOpNode),
+    UnaryOp(super::unary_op_node::UnaryOpNode),
+    FieldAccess(super::field_access_node::FieldAccessNode),
+    StructLiteral(super::struct_literal_node::StructLiteralNode),
+    StructUpdate(super::struct_update_node::StructUpdateNode),
+    Let(super::let_node::LetNode),
+    If(super::if_node::IfNode),
+    Match(super::match_node::MatchNode),
+    Lambda(super::lambda_node::LambdaNode),
+    Error(Span),
+}
+
+impl Expr {
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::Int(node) => node.span,
+            Expr::Float(node) => node.span,
+            Expr::Char(node) => node.span,
+            Expr::String(node) => node.span,
+            Expr::Bool(node) => node.span,
+            Expr::List(node) => node.span,
+            Expr::Variable(node) => node.name.span,
+            Expr::Function(node) => node.span,
+            Expr::Call(node) => node.span,
+            Expr::BinOp(node) => node.span,
+            Expr::UnaryOp(node) => node.span,
+            Expr::FieldAccess(node) => node.span,
+            Expr::StructLiteral(node) => node.span,
+            Expr::StructUpdate(node) => node.span,
+            Expr::Let(node) => node.span,
+            Expr::If(node) => node.span,
+            Expr::Match(node) => node.span,
+            Expr::Lambda(node) => node.span,
+            Expr::Error(span) => *span,
+        }
+    }
+}
+
+impl Node for Expr {
+    fn represent(&self) -> String {
+        match self {
+            Expr::Int(node) => node.represent(),
+            Expr::Float(node) => node.represent(),
+            Expr::Char(node) => node.represent(),
+            Expr::String(node) => node.represent(),
+            Expr::Bool(node) => node.represent(),
+            Expr::List(node) => node.represent(),
+            Expr::Variable(node) => node.represent(),
+            Expr::Function(node) => node.represent(),
+            Expr::Call(node) => node.represent(),
+            Expr::BinOp(node) => node.represent(),
+            Expr::UnaryOp(node) => node.represent(),
+            Expr::FieldAccess(node) => node.represent(),
+            Expr::StructLiteral(node) => node.represent(),
+            Expr::StructUpdate(node) => node.represent(),
+            Expr::Let(node) => node.represent(),
+            Expr::If(node) => node.represent(),
+            Expr::Match(node) => node.represent(),
+            Expr::Lambda(node) => node.represent(),
+            Expr::Error(_) => "ErrorNode".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pattern {
+    Literal(super::pattern_node::LiteralPatternNode),
+    Wildcard(Span),
+    Binding(Identifier),
+    Struct(super::pattern_node::StructPatternNode),
+    List(super::pattern_node::ListPatternNode),
+}
+
+impl Pattern {
+    // Finds the first literal in the pattern, for the places where there is
+    // no other arm to fall back on. Struct fields always exist. The length of
+    // a list is not checked, so `[a b]` is accepted here even though a list
+    // of another length would not match it.
+    pub fn refutable_part(&self) -> Option<Span> {
+        match self {
+            Pattern::Literal(node) => Some(node.span),
+            Pattern::Wildcard(_) | Pattern::Binding(_) => None,
+            Pattern::Struct(node) => node
+                .fields
+                .iter()
+                .find_map(|(_, pattern)| pattern.refutable_part()),
+            Pattern::List(node) => node
+                .patterns
+                .iter()
+                .chain(node.rest.as_deref())
+                .find_map(|pattern| pattern.refutable_part()),
+        }
+    }
+
+    // Finds the first list pattern, including one nested in a struct
+    // pattern.
+    pub fn list_part(&self) -> Option<Span> {
+        match self {
+            Pattern::List(node) => Some(node.span),
+            Pattern::Struct(node) => node
+                .fields
+                .iter()
+                .find_map(|(_, pattern)| pattern.list_part()),
+            Pattern::Literal(_) | Pattern::Wildcard(_) | Pattern::Binding(_) => None,
+        }
+    }
+
+    // Parameters and let bindings are nearly always plain names, so those
+    // are represented by the name alone.
+    pub fn represent_binding(&self) -> String {
+        match self {
+            Pattern::Binding(name) => name.name.clone(),
+            _ => self.represent(),
+        }
+    }
+}
+
+impl Node for Pattern {
+    fn represent(&self) -> String {
+        match self {
+            Pattern::Literal(node) => node.represent(),
+            Pattern::Wildcard(_) => "WildcardPatternNode".to_string(),
+            Pattern::Binding(name) => format!("BindingPatternNode<{}>", name.name),
+            Pattern::Struct(node) => node.represent(),
+            Pattern::List(node) => node.represent(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type {
+    Named(super::type_node::TypeNode),
+    Variable(super::type_variable_node::TypeVariableNode),
+    Union(super::union_type_node::UnionTypeNode),
+    Struct(super::struct_type_node::StructTypeNode),
+}
+
+impl Node for Type {
+    fn represent(&self) -> String {
+        match self {
+            Type::Named(node) => node.represent(),
+            Type::Variable(node) => node.represent(),
+            Type::Union(node) => node.represent(),
+            Type::Struct(node) => node.represent(),
+        }
+    }
+}
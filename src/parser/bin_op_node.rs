@@ -1,16 +1,60 @@
-#[derive(Debug)]
+use super::node::Node;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOperator {
+    Flow,
+    Or,
+    And,
+    Equal,
+    NotEqual,
+    LessThan,
+    LessThanOrEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+    Concatenate,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Power,
+}
+
+impl BinaryOperator {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            BinaryOperator::Flow => "|>",
+            BinaryOperator::Or => "||",
+            BinaryOperator::And => "&&",
+            BinaryOperator::Equal => "==",
+            BinaryOperator::NotEqual => "!=",
+            BinaryOperator::LessThan => "<",
+            BinaryOperator::LessThanOrEqual => "<=",
+            BinaryOperator::GreaterThan => ">",
+            BinaryOperator::GreaterThanOrEqual => ">=",
+            BinaryOperator::Concatenate => "++",
+            BinaryOperator::Add => "+",
+            BinaryOperator::Subtract => "-",
+            BinaryOperator::Multiply => "*",
+            BinaryOperator::Divide => "/",
+            BinaryOperator::Power => "^",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct BinOpNode {
-    pub left_node: Box<dyn super::node::Node>,
-    pub right_node: Box<dyn super::node::Node>,
-    pub token: super::super::tokenizer::Token,
+    pub left_node: Box<super::ast::Expr>,
+    pub right_node: Box<super::ast::Expr>,
+    pub operator: BinaryOperator,
+    pub span: super::super::span::Span,
 }
 
-impl super::node::Node for BinOpNode {
+impl Node for BinOpNode {
     fn represent(&self) -> String {
         format!(
             "BinOpNode<{},{},{}>",
             self.left_node.represent(),
-            self.token.value,
+            self.operator.symbol(),
             self.right_node.represent()
         )
     }
@@ -0,0 +1,11 @@
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoolNode {
+    pub value: bool,
+    pub span: super::super::span::Span,
+}
+
+impl super::node::Node for BoolNode {
+    fn represent(&self) -> String {
+        format!("BoolNode<{}>", self.value)
+    }
+}
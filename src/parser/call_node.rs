@@ -0,0 +1,18 @@
+use super::node::Node;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallNode {
+    pub function: Box<super::ast::Expr>,
+    pub arguments: Vec<super::ast::Expr>,
+    pub span: super::super::span::Span,
+}
+
+impl Node for CallNode {
+    fn represent(&self) -> String {
+        let mut parts = vec![self.function.represent()];
+        for argument in &self.arguments {
+            parts.push(argument.represent());
+        }
+        format!("CallNode<{}>", parts.join(","))
+    }
+}
@@ -0,0 +1,11 @@
+#[derive(Debug, Clone, PartialEq)]
+pub struct CharNode {
+    pub value: char,
+    pub span: super::super::span::Span,
+}
+
+impl super::node::Node for CharNode {
+    fn represent(&self) -> String {
+        format!("CharNode<{}>", self.value)
+    }
+}
@@ -0,0 +1,24 @@
+type Identifier = super::ast::Identifier;
+type Span = super::super::span::Span;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExposingNode {
+    All(Span),
+    Only(Vec<Identifier>, Span),
+}
+
+impl super::node::Node for ExposingNode {
+    fn represent(&self) -> String {
+        match self {
+            ExposingNode::All(_) => "ExposingNode<_>".to_string(),
+            ExposingNode::Only(names, _) => format!(
+                "ExposingNode<[{}]>",
+                names
+                    .iter()
+                    .map(|n| n.name.clone())
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+        }
+    }
+}
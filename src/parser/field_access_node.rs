@@ -0,0 +1,18 @@
+use super::node::Node;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldAccessNode {
+    pub node: Box<super::ast::Expr>,
+    pub field: super::ast::Identifier,
+    pub span: super::super::span::Span,
+}
+
+impl Node for FieldAccessNode {
+    fn represent(&self) -> String {
+        format!(
+            "FieldAccessNode<{},{}>",
+            self.node.represent(),
+            self.field.name
+        )
+    }
+}
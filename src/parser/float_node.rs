@@ -0,0 +1,11 @@
+#[derive(Debug, Clone, PartialEq)]
+pub struct FloatNode {
+    pub value: f64,
+    pub span: super::super::span::Span,
+}
+
+impl super::node::Node for FloatNode {
+    fn represent(&self) -> String {
+        format!("FloatNode<{}>", self.value)
+    }
+}
@@ -0,0 +1,31 @@
+use super::node::Node;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionDefinitionNode {
+    pub documentation: Option<String>,
+    pub signature: Option<super::signature_node::SignatureNode>,
+    pub name: super::ast::Identifier,
+    pub parameters: Vec<super::ast::Pattern>,
+    pub body: super::ast::Expr,
+    pub span: super::super::span::Span,
+}
+
+impl Node for FunctionDefinitionNode {
+    fn represent(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(signature) = &self.signature {
+            parts.push(signature.represent());
+        }
+        parts.push(self.name.name.clone());
+        parts.push(format!(
+            "[{}]",
+            self.parameters
+                .iter()
+                .map(|p| p.represent_binding())
+                .collect::<Vec<String>>()
+                .join(",")
+        ));
+        parts.push(self.body.represent());
+        format!("FunctionDefinitionNode<{}>", parts.join(","))
+    }
+}
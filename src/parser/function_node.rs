@@ -0,0 +1,15 @@
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionNode {
+    pub namespace: Option<super::ast::Identifier>,
+    pub name: super::ast::Identifier,
+    pub span: super::super::span::Span,
+}
+
+impl super::node::Node for FunctionNode {
+    fn represent(&self) -> String {
+        match &self.namespace {
+            Some(namespace) => format!("FunctionNode<{}.{}>", namespace.name, self.name.name),
+            None => format!("FunctionNode<{}>", self.name.name),
+        }
+    }
+}
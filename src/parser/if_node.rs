@@ -0,0 +1,26 @@
+use super::node::Node;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IfNode {
+    pub branches: Vec<(super::ast::Expr, super::ast::Expr)>,
+    pub else_branch: Box<super::ast::Expr>,
+    pub span: super::super::span::Span,
+}
+
+impl Node for IfNode {
+    fn represent(&self) -> String {
+        format!(
+            "IfNode<[{}],{}>",
+            self.branches
+                .iter()
+                .map(|(condition, branch)| format!(
+                    "{}=>{}",
+                    condition.represent(),
+                    branch.represent()
+                ))
+                .collect::<Vec<String>>()
+                .join(","),
+            self.else_branch.represent()
+        )
+    }
+}
@@ -0,0 +1,21 @@
+use super::node::Node;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportNode {
+    pub namespace: super::ast::Identifier,
+    pub exposing: Option<super::exposing_node::ExposingNode>,
+    pub span: super::super::span::Span,
+}
+
+impl Node for ImportNode {
+    fn represent(&self) -> String {
+        match &self.exposing {
+            Some(exposing) => format!(
+                "ImportNode<{},{}>",
+                self.namespace.name,
+                exposing.represent()
+            ),
+            None => format!("ImportNode<{}>", self.namespace.name),
+        }
+    }
+}
@@ -1,10 +1,11 @@
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct IntNode {
-    pub token: super::super::tokenizer::Token,
+    pub value: i64,
+    pub span: super::super::span::Span,
 }
 
 impl super::node::Node for IntNode {
     fn represent(&self) -> String {
-        format!("IntNode<{}>", self.token.value)
+        format!("IntNode<{}>", self.value)
     }
 }
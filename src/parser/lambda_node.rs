@@ -0,0 +1,22 @@
+use super::node::Node;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LambdaNode {
+    pub parameters: Vec<super::ast::Pattern>,
+    pub body: Box<super::ast::Expr>,
+    pub span: super::super::span::Span,
+}
+
+impl Node for LambdaNode {
+    fn represent(&self) -> String {
+        format!(
+            "LambdaNode<[{}],{}>",
+            self.parameters
+                .iter()
+                .map(|p| p.represent_binding())
+                .collect::<Vec<String>>()
+                .join(","),
+            self.body.represent()
+        )
+    }
+}
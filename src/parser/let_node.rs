@@ -0,0 +1,24 @@
+use super::node::Node;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LetNode {
+    pub bindings: Vec<(super::ast::Pattern, super::ast::Expr)>,
+    pub body: Box<super::ast::Expr>,
+    pub span: super::super::span::Span,
+}
+
+impl Node for LetNode {
+    fn represent(&self) -> String {
+        format!(
+            "LetNode<[{}],{}>",
+            self.bindings
+                .iter()
+                .map(|(pattern, value)| {
+                    format!("{}={}", pattern.represent_binding(), value.represent())
+                })
+                .collect::<Vec<String>>()
+                .join(","),
+            self.body.represent()
+        )
+    }
+}
@@ -0,0 +1,20 @@
+use super::node::Node;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListNode {
+    pub elements: Vec<super::ast::Expr>,
+    pub span: super::super::span::Span,
+}
+
+impl Node for ListNode {
+    fn represent(&self) -> String {
+        format!(
+            "ListNode<{}>",
+            self.elements
+                .iter()
+                .map(|element| element.represent())
+                .collect::<Vec<String>>()
+                .join(",")
+        )
+    }
+}
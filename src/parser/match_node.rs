@@ -0,0 +1,27 @@
+use super::node::Node;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchNode {
+    pub subject: Box<super::ast::Expr>,
+    pub arms: Vec<(super::ast::Pattern, super::ast::Expr)>,
+    pub else_branch: Option<Box<super::ast::Expr>>,
+    pub span: super::super::span::Span,
+}
+
+impl Node for MatchNode {
+    fn represent(&self) -> String {
+        let mut parts = vec![self.subject.represent()];
+        parts.push(format!(
+            "[{}]",
+            self.arms
+                .iter()
+                .map(|(pattern, branch)| format!("{}=>{}", pattern.represent(), branch.represent()))
+                .collect::<Vec<String>>()
+                .join(",")
+        ));
+        if let Some(else_branch) = &self.else_branch {
+            parts.push(else_branch.represent());
+        }
+        format!("MatchNode<{}>", parts.join(","))
+    }
+}
@@ -0,0 +1,18 @@
+use super::node::Node;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleHeaderNode {
+    pub namespace: super::ast::Identifier,
+    pub exposing: super::exposing_node::ExposingNode,
+    pub span: super::super::span::Span,
+}
+
+impl Node for ModuleHeaderNode {
+    fn represent(&self) -> String {
+        format!(
+            "ModuleHeaderNode<{},{}>",
+            self.namespace.name,
+            self.exposing.represent()
+        )
+    }
+}
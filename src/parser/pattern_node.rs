@@ -0,0 +1,56 @@
+use super::node::Node;
+
+type Pattern = super::ast::Pattern;
+type Span = super::super::span::Span;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiteralPatternNode {
+    pub value: super::ast::Literal,
+    pub span: Span,
+}
+
+impl Node for LiteralPatternNode {
+    fn represent(&self) -> String {
+        format!("LiteralPatternNode<{}>", self.value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructPatternNode {
+    pub fields: Vec<(super::ast::Identifier, Pattern)>,
+    pub span: Span,
+}
+
+impl Node for StructPatternNode {
+    fn represent(&self) -> String {
+        format!(
+            "StructPatternNode<{}>",
+            self.fields
+                .iter()
+                .map(|(name, pattern)| format!("{}:{}", name.name, pattern.represent()))
+                .collect::<Vec<String>>()
+                .join(",")
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListPatternNode {
+    pub patterns: Vec<Pattern>,
+    pub rest: Option<Box<Pattern>>,
+    pub span: Span,
+}
+
+impl Node for ListPatternNode {
+    fn represent(&self) -> String {
+        let mut parts = self
+            .patterns
+            .iter()
+            .map(|pattern| pattern.represent())
+            .collect::<Vec<String>>();
+        if let Some(rest) = &self.rest {
+            parts.push(format!("..{}", rest.represent()));
+        }
+        format!("ListPatternNode<{}>", parts.join(","))
+    }
+}
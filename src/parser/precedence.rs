@@ -0,0 +1,115 @@
+use super::bin_op_node::BinaryOperator;
+use super::unary_op_node::UnaryOperator;
+
+type TokenType = super::super::tokenizer::TokenType;
+
+pub enum Associativity {
+    Left,
+    Right,
+}
+
+// Binary operators from loosest to tightest binding.
+const INFIX_OPERATORS: &[(TokenType, BinaryOperator, u8, Associativity)] = &[
+    (
+        TokenType::Flow,
+        BinaryOperator::Flow,
+        1,
+        Associativity::Left,
+    ),
+    (TokenType::Or, BinaryOperator::Or, 2, Associativity::Left),
+    (TokenType::And, BinaryOperator::And, 3, Associativity::Left),
+    (
+        TokenType::DoubleEquals,
+        BinaryOperator::Equal,
+        4,
+        Associativity::Left,
+    ),
+    (
+        TokenType::NotEquals,
+        BinaryOperator::NotEqual,
+        4,
+        Associativity::Left,
+    ),
+    (
+        TokenType::LessThan,
+        BinaryOperator::LessThan,
+        4,
+        Associativity::Left,
+    ),
+    (
+        TokenType::LessThanEquals,
+        BinaryOperator::LessThanOrEqual,
+        4,
+        Associativity::Left,
+    ),
+    (
+        TokenType::GreaterThan,
+        BinaryOperator::GreaterThan,
+        4,
+        Associativity::Left,
+    ),
+    (
+        TokenType::GreaterThanEquals,
+        BinaryOperator::GreaterThanOrEqual,
+        4,
+        Associativity::Left,
+    ),
+    (
+        TokenType::DoublePlus,
+        BinaryOperator::Concatenate,
+        5,
+        Associativity::Right,
+    ),
+    (TokenType::Plus, BinaryOperator::Add, 6, Associativity::Left),
+    (
+        TokenType::Dash,
+        BinaryOperator::Subtract,
+        6,
+        Associativity::Left,
+    ),
+    (
+        TokenType::Star,
+        BinaryOperator::Multiply,
+        7,
+        Associativity::Left,
+    ),
+    (
+        TokenType::ForwardSlash,
+        BinaryOperator::Divide,
+        7,
+        Associativity::Left,
+    ),
+    (
+        TokenType::Caret,
+        BinaryOperator::Power,
+        9,
+        Associativity::Right,
+    ),
+];
+
+// Prefix operators bind tighter than `*` but looser than `^`, so `-2 ^ 2`
+// is `-(2 ^ 2)`.
+const PREFIX_OPERATORS: &[(TokenType, UnaryOperator, u8)] = &[
+    (TokenType::Not, UnaryOperator::Not, 8),
+    (TokenType::Plus, UnaryOperator::Plus, 8),
+    (TokenType::Dash, UnaryOperator::Negate, 8),
+];
+
+pub fn infix_operator(token_type: &TokenType) -> Option<(BinaryOperator, u8, u8)> {
+    INFIX_OPERATORS
+        .iter()
+        .find(|(token, _, _, _)| token == token_type)
+        .map(
+            |(_, operator, precedence, associativity)| match associativity {
+                Associativity::Left => (*operator, precedence * 2, precedence * 2 + 1),
+                Associativity::Right => (*operator, precedence * 2 + 1, precedence * 2),
+            },
+        )
+}
+
+pub fn prefix_operator(token_type: &TokenType) -> Option<(UnaryOperator, u8)> {
+    PREFIX_OPERATORS
+        .iter()
+        .find(|(token, _, _)| token == token_type)
+        .map(|(_, operator, precedence)| (*operator, precedence * 2))
+}
@@ -0,0 +1,31 @@
+use super::node::Node;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgramNode {
+    pub header: Option<super::module_header_node::ModuleHeaderNode>,
+    pub imports: Vec<super::import_node::ImportNode>,
+    pub declarations: Vec<super::ast::Decl>,
+}
+
+impl Node for ProgramNode {
+    fn represent(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(header) = &self.header {
+            parts.push(header.represent());
+        }
+        for import in &self.imports {
+            parts.push(import.represent());
+        }
+        for declaration in &self.declarations {
+            parts.push(declaration.represent());
+        }
+        format!("ProgramNode<{}>", parts.join(","))
+    }
+}
+
+impl fmt::Display for ProgramNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.represent())
+    }
+}
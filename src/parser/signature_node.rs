@@ -0,0 +1,18 @@
+use super::node::Node;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignatureNode {
+    pub name: super::ast::Identifier,
+    pub type_node: super::ast::Type,
+    pub span: super::super::span::Span,
+}
+
+impl Node for SignatureNode {
+    fn represent(&self) -> String {
+        format!(
+            "SignatureNode<{},{}>",
+            self.name.name,
+            self.type_node.represent()
+        )
+    }
+}
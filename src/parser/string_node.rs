@@ -0,0 +1,11 @@
+#[derive(Debug, Clone, PartialEq)]
+pub struct StringNode {
+    pub value: String,
+    pub span: super::super::span::Span,
+}
+
+impl super::node::Node for StringNode {
+    fn represent(&self) -> String {
+        format!("StringNode<{}>", self.value)
+    }
+}
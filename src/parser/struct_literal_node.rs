@@ -0,0 +1,20 @@
+use super::node::Node;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructLiteralNode {
+    pub fields: Vec<(super::ast::Identifier, super::ast::Expr)>,
+    pub span: super::super::span::Span,
+}
+
+impl Node for StructLiteralNode {
+    fn represent(&self) -> String {
+        format!(
+            "StructLiteralNode<{}>",
+            self.fields
+                .iter()
+                .map(|(name, value)| format!("{}:{}", name.name, value.represent()))
+                .collect::<Vec<String>>()
+                .join(",")
+        )
+    }
+}
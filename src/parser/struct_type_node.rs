@@ -0,0 +1,20 @@
+use super::node::Node;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructTypeNode {
+    pub fields: Vec<(super::ast::Identifier, super::ast::Type)>,
+    pub span: super::super::span::Span,
+}
+
+impl Node for StructTypeNode {
+    fn represent(&self) -> String {
+        format!(
+            "StructTypeNode<{}>",
+            self.fields
+                .iter()
+                .map(|(name, field_type)| format!("{}:{}", name.name, field_type.represent()))
+                .collect::<Vec<String>>()
+                .join(",")
+        )
+    }
+}
@@ -0,0 +1,18 @@
+use super::node::Node;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructUpdateNode {
+    pub base: Box<super::ast::Expr>,
+    pub fields: Vec<(super::ast::Identifier, super::ast::Expr)>,
+    pub span: super::super::span::Span,
+}
+
+impl Node for StructUpdateNode {
+    fn represent(&self) -> String {
+        let mut parts = vec![self.base.represent()];
+        for (name, value) in &self.fields {
+            parts.push(format!("{}:{}", name.name, value.represent()));
+        }
+        format!("StructUpdateNode<{}>", parts.join(","))
+    }
+}
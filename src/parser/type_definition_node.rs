@@ -0,0 +1,25 @@
+use super::node::Node;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeDefinitionNode {
+    pub documentation: Option<String>,
+    pub name: super::ast::Identifier,
+    pub parameters: Vec<super::ast::Identifier>,
+    pub type_node: super::ast::Type,
+    pub span: super::super::span::Span,
+}
+
+impl Node for TypeDefinitionNode {
+    fn represent(&self) -> String {
+        format!(
+            "TypeDefinitionNode<{},[{}],{}>",
+            self.name.name,
+            self.parameters
+                .iter()
+                .map(|p| p.name.clone())
+                .collect::<Vec<String>>()
+                .join(","),
+            self.type_node.represent()
+        )
+    }
+}
@@ -0,0 +1,18 @@
+use super::node::Node;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeNode {
+    pub name: super::ast::Identifier,
+    pub arguments: Vec<super::ast::Type>,
+    pub span: super::super::span::Span,
+}
+
+impl Node for TypeNode {
+    fn represent(&self) -> String {
+        let mut parts = vec![self.name.name.clone()];
+        for argument in &self.arguments {
+            parts.push(argument.represent());
+        }
+        format!("TypeNode<{}>", parts.join(","))
+    }
+}
@@ -0,0 +1,10 @@
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeVariableNode {
+    pub name: super::ast::Identifier,
+}
+
+impl super::node::Node for TypeVariableNode {
+    fn represent(&self) -> String {
+        format!("TypeVariableNode<{}>", self.name.name)
+    }
+}
@@ -1,14 +1,34 @@
-#[derive(Debug)]
+use super::node::Node;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnaryOperator {
+    Not,
+    Plus,
+    Negate,
+}
+
+impl UnaryOperator {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            UnaryOperator::Not => "!",
+            UnaryOperator::Plus => "+",
+            UnaryOperator::Negate => "-",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct UnaryOpNode {
-    pub node: Box<dyn super::node::Node>,
-    pub token: super::super::tokenizer::Token,
+    pub node: Box<super::ast::Expr>,
+    pub operator: UnaryOperator,
+    pub span: super::super::span::Span,
 }
 
-impl super::node::Node for UnaryOpNode {
+impl Node for UnaryOpNode {
     fn represent(&self) -> String {
         format!(
             "UnaryOpNode<{},{}>",
-            self.token.value,
+            self.operator.symbol(),
             self.node.represent()
         )
     }
@@ -0,0 +1,20 @@
+use super::node::Node;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnionTypeNode {
+    pub types: Vec<super::ast::Type>,
+    pub span: super::super::span::Span,
+}
+
+impl Node for UnionTypeNode {
+    fn represent(&self) -> String {
+        format!(
+            "UnionTypeNode<{}>",
+            self.types
+                .iter()
+                .map(|t| t.represent())
+                .collect::<Vec<String>>()
+                .join(",")
+        )
+    }
+}
@@ -0,0 +1,10 @@
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariableNode {
+    pub name: super::ast::Identifier,
+}
+
+impl super::node::Node for VariableNode {
+    fn represent(&self) -> String {
+        format!("VariableNode<{}>", self.name.name)
+    }
+}
@@ -0,0 +1,655 @@
+use super::ast::{Decl, Expr, Pattern, Type};
+use super::bin_op_node::BinOpNode;
+use super::call_node::CallNode;
+use super::field_access_node::FieldAccessNode;
+use super::function_definition_node::FunctionDefinitionNode;
+use super::function_node::FunctionNode;
+use super::if_node::IfNode;
+use super::lambda_node::LambdaNode;
+use super::let_node::LetNode;
+use super::list_node::ListNode;
+use super::match_node::MatchNode;
+use super::program_node::ProgramNode;
+use super::signature_node::SignatureNode;
+use super::struct_literal_node::StructLiteralNode;
+use super::struct_update_node::StructUpdateNode;
+use super::type_definition_node::TypeDefinitionNode;
+use super::unary_op_node::UnaryOpNode;
+use super::variable_node::VariableNode;
+
+// Read-only traversal of the AST. Every method defaults to the matching
+// `walk_` function, which visits the node's children, so a pass only
+// overrides the nodes it is interested in and calls `walk_` itself if it
+// still wants to recurse, e.g. after pushing a scope in `visit_let`.
+pub trait Visitor {
+    fn visit_program(&mut self, program: &ProgramNode) {
+        walk_program(self, program);
+    }
+
+    fn visit_decl(&mut self, decl: &Decl) {
+        walk_decl(self, decl);
+    }
+
+    fn visit_function_definition(&mut self, node: &FunctionDefinitionNode) {
+        walk_function_definition(self, node);
+    }
+
+    fn visit_signature(&mut self, node: &SignatureNode) {
+        walk_signature(self, node);
+    }
+
+    fn visit_type_definition(&mut self, node: &TypeDefinitionNode) {
+        walk_type_definition(self, node);
+    }
+
+    fn visit_type(&mut self, node: &Type) {
+        walk_type(self, node);
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        walk_expr(self, expr);
+    }
+
+    fn visit_variable(&mut self, _node: &VariableNode) {}
+
+    fn visit_function(&mut self, _node: &FunctionNode) {}
+
+    fn visit_list(&mut self, node: &ListNode) {
+        walk_list(self, node);
+    }
+
+    fn visit_call(&mut self, node: &CallNode) {
+        walk_call(self, node);
+    }
+
+    fn visit_bin_op(&mut self, node: &BinOpNode) {
+        walk_bin_op(self, node);
+    }
+
+    fn visit_unary_op(&mut self, node: &UnaryOpNode) {
+        walk_unary_op(self, node);
+    }
+
+    fn visit_field_access(&mut self, node: &FieldAccessNode) {
+        walk_field_access(self, node);
+    }
+
+    fn visit_struct_literal(&mut self, node: &StructLiteralNode) {
+        walk_struct_literal(self, node);
+    }
+
+    fn visit_struct_update(&mut self, node: &StructUpdateNode) {
+        walk_struct_update(self, node);
+    }
+
+    fn visit_let(&mut self, node: &LetNode) {
+        walk_let(self, node);
+    }
+
+    fn visit_if(&mut self, node: &IfNode) {
+        walk_if(self, node);
+    }
+
+    fn visit_match(&mut self, node: &MatchNode) {
+        walk_match(self, node);
+    }
+
+    fn visit_lambda(&mut self, node: &LambdaNode) {
+        walk_lambda(self, node);
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        walk_pattern(self, pattern);
+    }
+}
+
+pub fn walk_program<V: Visitor + ?Sized>(visitor: &mut V, program: &ProgramNode) {
+    for declaration in &program.declarations {
+        visitor.visit_decl(declaration);
+    }
+}
+
+pub fn walk_decl<V: Visitor + ?Sized>(visitor: &mut V, decl: &Decl) {
+    match decl {
+        Decl::Function(node) => visitor.visit_function_definition(node),
+        Decl::Type(node) => visitor.visit_type_definition(node),
+        Decl::Error(_) => {}
+    }
+}
+
+pub fn walk_function_definition<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    node: &FunctionDefinitionNode,
+) {
+    if let Some(signature) = &node.signature {
+        visitor.visit_signature(signature);
+    }
+    for parameter in &node.parameters {
+        visitor.visit_pattern(parameter);
+    }
+    visitor.visit_expr(&node.body);
+}
+
+pub fn walk_signature<V: Visitor + ?Sized>(visitor: &mut V, node: &SignatureNode) {
+    visitor.visit_type(&node.type_node);
+}
+
+pub fn walk_type_definition<V: Visitor + ?Sized>(visitor: &mut V, node: &TypeDefinitionNode) {
+    visitor.visit_type(&node.type_node);
+}
+
+pub fn walk_type<V: Visitor + ?Sized>(visitor: &mut V, node: &Type) {
+    match node {
+        Type::Named(node) => {
+            for argument in &node.arguments {
+                visitor.visit_type(argument);
+            }
+        }
+        Type::Union(node) => {
+            for member in &node.types {
+                visitor.visit_type(member);
+            }
+        }
+        Type::Struct(node) => {
+            for (_, field_type) in &node.fields {
+                visitor.visit_type(field_type);
+            }
+        }
+        Type::Variable(_) => {}
+    }
+}
+
+pub fn walk_expr<V: Visitor + ?Sized>(visitor: &mut V, expr: &Expr) {
+    match expr {
+        Expr::Variable(node) => visitor.visit_variable(node),
+        Expr::Function(node) => visitor.visit_function(node),
+        Expr::List(node) => visitor.visit_list(node),
+        Expr::Call(node) => visitor.visit_call(node),
+        Expr::BinOp(node) => visitor.visit_bin_op(node),
+        Expr::UnaryOp(node) => visitor.visit_unary_op(node),
+        Expr::FieldAccess(node) => visitor.visit_field_access(node),
+        Expr::StructLiteral(node) => visitor.visit_struct_literal(node),
+        Expr::StructUpdate(node) => visitor.visit_struct_update(node),
+        Expr::Let(node) => visitor.visit_let(node),
+        Expr::If(node) => visitor.visit_if(node),
+        Expr::Match(node) => visitor.visit_match(node),
+        Expr::Lambda(node) => visitor.visit_lambda(node),
+        Expr::Int(_)
+        | Expr::Float(_)
+        | Expr::Char(_)
+        | Expr::String(_)
+        | Expr::Bool(_)
+        | Expr::Error(_) => {}
+    }
+}
+
+pub fn walk_list<V: Visitor + ?Sized>(visitor: &mut V, node: &ListNode) {
+    for element in &node.elements {
+        visitor.visit_expr(element);
+    }
+}
+
+pub fn walk_call<V: Visitor + ?Sized>(visitor: &mut V, node: &CallNode) {
+    visitor.visit_expr(&node.function);
+    for argument in &node.arguments {
+        visitor.visit_expr(argument);
+    }
+}
+
+pub fn walk_bin_op<V: Visitor + ?Sized>(visitor: &mut V, node: &BinOpNode) {
+    visitor.visit_expr(&node.left_node);
+    visitor.visit_expr(&node.right_node);
+}
+
+pub fn walk_unary_op<V: Visitor + ?Sized>(visitor: &mut V, node: &UnaryOpNode) {
+    visitor.visit_expr(&node.node);
+}
+
+pub fn walk_field_access<V: Visitor + ?Sized>(visitor: &mut V, node: &FieldAccessNode) {
+    visitor.visit_expr(&node.node);
+}
+
+pub fn walk_struct_literal<V: Visitor + ?Sized>(visitor: &mut V, node: &StructLiteralNode) {
+    for (_, value) in &node.fields {
+        visitor.visit_expr(value);
+    }
+}
+
+pub fn walk_struct_update<V: Visitor + ?Sized>(visitor: &mut V, node: &StructUpdateNode) {
+    visitor.visit_expr(&node.base);
+    for (_, value) in &node.fields {
+        visitor.visit_expr(value);
+    }
+}
+
+pub fn walk_let<V: Visitor + ?Sized>(visitor: &mut V, node: &LetNode) {
+    for (pattern, value) in &node.bindings {
+        visitor.visit_pattern(pattern);
+        visitor.visit_expr(value);
+    }
+    visitor.visit_expr(&node.body);
+}
+
+pub fn walk_if<V: Visitor + ?Sized>(visitor: &mut V, node: &IfNode) {
+    for (condition, branch) in &node.branches {
+        visitor.visit_expr(condition);
+        visitor.visit_expr(branch);
+    }
+    visitor.visit_expr(&node.else_branch);
+}
+
+pub fn walk_match<V: Visitor + ?Sized>(visitor: &mut V, node: &MatchNode) {
+    visitor.visit_expr(&node.subject);
+    for (pattern, branch) in &node.arms {
+        visitor.visit_pattern(pattern);
+        visitor.visit_expr(branch);
+    }
+    if let Some(else_branch) = &node.else_branch {
+        visitor.visit_expr(else_branch);
+    }
+}
+
+pub fn walk_lambda<V: Visitor + ?Sized>(visitor: &mut V, node: &LambdaNode) {
+    for parameter in &node.parameters {
+        visitor.visit_pattern(parameter);
+    }
+    visitor.visit_expr(&node.body);
+}
+
+pub fn walk_pattern<V: Visitor + ?Sized>(visitor: &mut V, pattern: &Pattern) {
+    match pattern {
+        Pattern::Struct(node) => {
+            for (_, field) in &node.fields {
+                visitor.visit_pattern(field);
+            }
+        }
+        Pattern::List(node) => {
+            for element in &node.patterns {
+                visitor.visit_pattern(element);
+            }
+            if let Some(rest) = &node.rest {
+                visitor.visit_pattern(rest);
+            }
+        }
+        Pattern::Literal(_) | Pattern::Wildcard(_) | Pattern::Binding(_) => {}
+    }
+}
+
+// Traversal that may rewrite the AST in place. A pass can replace a whole
+// expression by assigning to it in `visit_expr`, e.g. to desugar or fold
+// constants, and the defaults carry on into the children.
+pub trait MutVisitor {
+    fn visit_program(&mut self, program: &mut ProgramNode) {
+        walk_program_mut(self, program);
+    }
+
+    fn visit_decl(&mut self, decl: &mut Decl) {
+        walk_decl_mut(self, decl);
+    }
+
+    fn visit_function_definition(&mut self, node: &mut FunctionDefinitionNode) {
+        walk_function_definition_mut(self, node);
+    }
+
+    fn visit_signature(&mut self, node: &mut SignatureNode) {
+        walk_signature_mut(self, node);
+    }
+
+    fn visit_type_definition(&mut self, node: &mut TypeDefinitionNode) {
+        walk_type_definition_mut(self, node);
+    }
+
+    fn visit_type(&mut self, node: &mut Type) {
+        walk_type_mut(self, node);
+    }
+
+    fn visit_expr(&mut self, expr: &mut Expr) {
+        walk_expr_mut(self, expr);
+    }
+
+    fn visit_variable(&mut self, _node: &mut VariableNode) {}
+
+    fn visit_function(&mut self, _node: &mut FunctionNode) {}
+
+    fn visit_list(&mut self, node: &mut ListNode) {
+        walk_list_mut(self, node);
+    }
+
+    fn visit_call(&mut self, node: &mut CallNode) {
+        walk_call_mut(self, node);
+    }
+
+    fn visit_bin_op(&mut self, node: &mut BinOpNode) {
+        walk_bin_op_mut(self, node);
+    }
+
+    fn visit_unary_op(&mut self, node: &mut UnaryOpNode) {
+        walk_unary_op_mut(self, node);
+    }
+
+    fn visit_field_access(&mut self, node: &mut FieldAccessNode) {
+        walk_field_access_mut(self, node);
+    }
+
+    fn visit_struct_literal(&mut self, node: &mut StructLiteralNode) {
+        walk_struct_literal_mut(self, node);
+    }
+
+    fn visit_struct_update(&mut self, node: &mut StructUpdateNode) {
+        walk_struct_update_mut(self, node);
+    }
+
+    fn visit_let(&mut self, node: &mut LetNode) {
+        walk_let_mut(self, node);
+    }
+
+    fn visit_if(&mut self, node: &mut IfNode) {
+        walk_if_mut(self, node);
+    }
+
+    fn visit_match(&mut self, node: &mut MatchNode) {
+        walk_match_mut(self, node);
+    }
+
+    fn visit_lambda(&mut self, node: &mut LambdaNode) {
+        walk_lambda_mut(self, node);
+    }
+
+    fn visit_pattern(&mut self, pattern: &mut Pattern) {
+        walk_pattern_mut(self, pattern);
+    }
+}
+
+pub fn walk_program_mut<V: MutVisitor + ?Sized>(visitor: &mut V, program: &mut ProgramNode) {
+    for declaration in &mut program.declarations {
+        visitor.visit_decl(declaration);
+    }
+}
+
+pub fn walk_decl_mut<V: MutVisitor + ?Sized>(visitor: &mut V, decl: &mut Decl) {
+    match decl {
+        Decl::Function(node) => visitor.visit_function_definition(node),
+        Decl::Type(node) => visitor.visit_type_definition(node),
+        Decl::Error(_) => {}
+    }
+}
+
+pub fn walk_function_definition_mut<V: MutVisitor + ?Sized>(
+    visitor: &mut V,
+    node: &mut FunctionDefinitionNode,
+) {
+    if let Some(signature) = &mut node.signature {
+        visitor.visit_signature(signature);
+    }
+    for parameter in &mut node.parameters {
+        visitor.visit_pattern(parameter);
+    }
+    visitor.visit_expr(&mut node.body);
+}
+
+pub fn walk_signature_mut<V: MutVisitor + ?Sized>(visitor: &mut V, node: &mut SignatureNode) {
+    visitor.visit_type(&mut node.type_node);
+}
+
+pub fn walk_type_definition_mut<V: MutVisitor + ?Sized>(
+    visitor: &mut V,
+    node: &mut TypeDefinitionNode,
+) {
+    visitor.visit_type(&mut node.type_node);
+}
+
+pub fn walk_type_mut<V: MutVisitor + ?Sized>(visitor: &mut V, node: &mut Type) {
+    match node {
+        Type::Named(node) => {
+            for argument in &mut node.arguments {
+                visitor.visit_type(argument);
+            }
+        }
+        Type::Union(node) => {
+            for member in &mut node.types {
+                visitor.visit_type(member);
+            }
+        }
+        Type::Struct(node) => {
+            for (_, field_type) in &mut node.fields {
+                visitor.visit_type(field_type);
+            }
+        }
+        Type::Variable(_) => {}
+    }
+}
+
+pub fn walk_expr_mut<V: MutVisitor + ?Sized>(visitor: &mut V, expr: &mut Expr) {
+    match expr {
+        Expr::Variable(node) => visitor.visit_variable(node),
+        Expr::Function(node) => visitor.visit_function(node),
+        Expr::List(node) => visitor.visit_list(node),
+        Expr::Call(node) => visitor.visit_call(node),
+        Expr::BinOp(node) => visitor.visit_bin_op(node),
+        Expr::UnaryOp(node) => visitor.visit_unary_op(node),
+        Expr::FieldAccess(node) => visitor.visit_field_access(node),
+        Expr::StructLiteral(node) => visitor.visit_struct_literal(node),
+        Expr::StructUpdate(node) => visitor.visit_struct_update(node),
+        Expr::Let(node) => visitor.visit_let(node),
+        Expr::If(node) => visitor.visit_if(node),
+        Expr::Match(node) => visitor.visit_match(node),
+        Expr::Lambda(node) => visitor.visit_lambda(node),
+        Expr::Int(_)
+        | Expr::Float(_)
+        | Expr::Char(_)
+        | Expr::String(_)
+        | Expr::Bool(_)
+        | Expr::Error(_) => {}
+    }
+}
+
+pub fn walk_list_mut<V: MutVisitor + ?Sized>(visitor: &mut V, node: &mut ListNode) {
+    for element in &mut node.elements {
+        visitor.visit_expr(element);
+    }
+}
+
+pub fn walk_call_mut<V: MutVisitor + ?Sized>(visitor: &mut V, node: &mut CallNode) {
+    visitor.visit_expr(&mut node.function);
+    for argument in &mut node.arguments {
+        visitor.visit_expr(argument);
+    }
+}
+
+pub fn walk_bin_op_mut<V: MutVisitor + ?Sized>(visitor: &mut V, node: &mut BinOpNode) {
+    visitor.visit_expr(&mut node.left_node);
+    visitor.visit_expr(&mut node.right_node);
+}
+
+pub fn walk_unary_op_mut<V: MutVisitor + ?Sized>(visitor: &mut V, node: &mut UnaryOpNode) {
+    visitor.visit_expr(&mut node.node);
+}
+
+pub fn walk_field_access_mut<V: MutVisitor + ?Sized>(visitor: &mut V, node: &mut FieldAccessNode) {
+    visitor.visit_expr(&mut node.node);
+}
+
+pub fn walk_struct_literal_mut<V: MutVisitor + ?Sized>(
+    visitor: &mut V,
+    node: &mut StructLiteralNode,
+) {
+    for (_, value) in &mut node.fields {
+        visitor.visit_expr(value);
+    }
+}
+
+pub fn walk_struct_update_mut<V: MutVisitor + ?Sized>(
+    visitor: &mut V,
+    node: &mut StructUpdateNode,
+) {
+    visitor.visit_expr(&mut node.base);
+    for (_, value) in &mut node.fields {
+        visitor.visit_expr(value);
+    }
+}
+
+pub fn walk_let_mut<V: MutVisitor + ?Sized>(visitor: &mut V, node: &mut LetNode) {
+    for (pattern, value) in &mut node.bindings {
+        visitor.visit_pattern(pattern);
+        visitor.visit_expr(value);
+    }
+    visitor.visit_expr(&mut node.body);
+}
+
+pub fn walk_if_mut<V: MutVisitor + ?Sized>(visitor: &mut V, node: &mut IfNode) {
+    for (condition, branch) in &mut node.branches {
+        visitor.visit_expr(condition);
+        visitor.visit_expr(branch);
+    }
+    visitor.visit_expr(&mut node.else_branch);
+}
+
+pub fn walk_match_mut<V: MutVisitor + ?Sized>(visitor: &mut V, node: &mut MatchNode) {
+    visitor.visit_expr(&mut node.subject);
+    for (pattern, branch) in &mut node.arms {
+        visitor.visit_pattern(pattern);
+        visitor.visit_expr(branch);
+    }
+    if let Some(else_branch) = &mut node.else_branch {
+        visitor.visit_expr(else_branch);
+    }
+}
+
+pub fn walk_lambda_mut<V: MutVisitor + ?Sized>(visitor: &mut V, node: &mut LambdaNode) {
+    for parameter in &mut node.parameters {
+        visitor.visit_pattern(parameter);
+    }
+    visitor.visit_expr(&mut node.body);
+}
+
+pub fn walk_pattern_mut<V: MutVisitor + ?Sized>(visitor: &mut V, pattern: &mut Pattern) {
+    match pattern {
+        Pattern::Struct(node) => {
+            for (_, field) in &mut node.fields {
+                visitor.visit_pattern(field);
+            }
+        }
+        Pattern::List(node) => {
+            for element in &mut node.patterns {
+                visitor.visit_pattern(element);
+            }
+            if let Some(rest) = &mut node.rest {
+                visitor.visit_pattern(rest);
+            }
+        }
+        Pattern::Literal(_) | Pattern::Wildcard(_) | Pattern::Binding(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::ast::Expr;
+    use super::super::bin_op_node::{BinOpNode, BinaryOperator};
+    use super::super::int_node::IntNode;
+    use super::super::let_node::LetNode;
+    use super::super::node::Node;
+    use super::super::variable_node::VariableNode;
+    use super::{MutVisitor, Visitor};
+
+    struct VariableCollector {
+        names: Vec<String>,
+    }
+
+    impl Visitor for VariableCollector {
+        fn visit_variable(&mut self, node: &VariableNode) {
+            self.names.push(node.name.name.clone());
+        }
+    }
+
+    struct ScopeDepths {
+        depth: usize,
+        depths: Vec<(String, usize)>,
+    }
+
+    impl Visitor for ScopeDepths {
+        fn visit_let(&mut self, node: &LetNode) {
+            self.depth += 1;
+            super::walk_let(self, node);
+            self.depth -= 1;
+        }
+
+        fn visit_variable(&mut self, node: &VariableNode) {
+            self.depths.push((node.name.name.clone(), self.depth));
+        }
+    }
+
+    struct AdditionFolder;
+
+    impl MutVisitor for AdditionFolder {
+        fn visit_expr(&mut self, expr: &mut Expr) {
+            super::walk_expr_mut(self, expr);
+
+            if let Expr::BinOp(BinOpNode {
+                left_node,
+                right_node,
+                operator: BinaryOperator::Add,
+                span,
+            }) = expr
+            {
+                if let (Expr::Int(left), Expr::Int(right)) =
+                    (left_node.as_ref(), right_node.as_ref())
+                {
+                    *expr = Expr::Int(IntNode {
+                        value: left.value + right.value,
+                        span: *span,
+                    });
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn visitors_should_reach_nested_expressions() {
+        let program = parse(
+            "Main a b =\n  match a\n  when { x: y }\n    let c = [b (-y)]\n    in c.first + a\n  else\n    if b\n      F(a)\n    else\n      { a <- x: b }",
+        );
+        let mut collector = VariableCollector { names: Vec::new() };
+        collector.visit_program(&program);
+        assert_eq!(
+            collector.names,
+            vec!["a", "b", "y", "c", "a", "b", "a", "a", "b"]
+        );
+    }
+
+    #[test]
+    fn overridden_visits_should_recurse_through_walk_functions() {
+        let program = parse("Main a = let b = let c = a in c in b + a");
+        let mut scopes = ScopeDepths {
+            depth: 0,
+            depths: Vec::new(),
+        };
+        scopes.visit_program(&program);
+        let depths: Vec<(&str, usize)> = scopes
+            .depths
+            .iter()
+            .map(|(name, depth)| (name.as_str(), *depth))
+            .collect();
+        assert_eq!(depths, vec![("a", 2), ("c", 2), ("b", 1), ("a", 1)]);
+    }
+
+    #[test]
+    fn mutable_visitors_should_rewrite_expressions_in_place() {
+        let mut program = parse("Main x = (1 + 2 + 3) * x\nOther = [(1 + 1) 2]");
+        AdditionFolder.visit_program(&mut program);
+        assert_eq!(
+            program.represent(),
+            "ProgramNode<FunctionDefinitionNode<Main,[x],BinOpNode<IntNode<6>,*,VariableNode<x>>>,FunctionDefinitionNode<Other,[],ListNode<IntNode<2>,IntNode<2>>>>"
+        );
+    }
+
+    fn parse(input: &str) -> super::super::program_node::ProgramNode {
+        let mut t = super::super::super::tokenizer::Tokenizer::build(Default::default(), input);
+        let tokens = t.execute().unwrap();
+        let mut l = super::super::super::layout::Layout::build(input, tokens);
+        let tokens = l.execute().unwrap();
+        let mut p = super::super::Parser::build(tokens);
+        let (program, errors) = p.execute();
+        assert_eq!(errors.len(), 0);
+        program
+    }
+}
@@ -0,0 +1,112 @@
+// Identifies one source file within a `SourceMap`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FileId(u32);
+
+struct SourceFile {
+    name: String,
+    source: String,
+    line_starts: Vec<usize>,
+}
+
+// Owns the text of every file being compiled. Spans only record byte
+// offsets, so lines and columns are worked out here when they are needed,
+// for example to render an error.
+#[derive(Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    pub fn new() -> SourceMap {
+        SourceMap { files: Vec::new() }
+    }
+
+    pub fn add(&mut self, name: &str, source: &str) -> FileId {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        self.files.push(SourceFile {
+            name: name.to_string(),
+            source: source.to_string(),
+            line_starts,
+        });
+        FileId(self.files.len() as u32 - 1)
+    }
+
+    pub fn name(&self, file_id: FileId) -> &str {
+        &self.file(file_id).name
+    }
+
+    pub fn source(&self, file_id: FileId) -> &str {
+        &self.file(file_id).source
+    }
+
+    // Lines are numbered from 1 and contain the byte at `offset`.
+    pub fn line_number(&self, file_id: FileId, offset: usize) -> usize {
+        let line_starts = &self.file(file_id).line_starts;
+        match line_starts.binary_search(&offset) {
+            Ok(index) => index + 1,
+            Err(index) => index,
+        }
+    }
+
+    // Columns are numbered from 0 and count characters rather than bytes, so
+    // they line up with the text when it is printed.
+    pub fn column(&self, file_id: FileId, offset: usize) -> usize {
+        let file = self.file(file_id);
+        let line_start = file.line_starts[self.line_number(file_id, offset) - 1];
+        file.source[line_start..]
+            .char_indices()
+            .take_while(|(index, _)| line_start + index < offset)
+            .count()
+    }
+
+    // The text of a line, without its line ending.
+    pub fn line(&self, file_id: FileId, line_number: usize) -> &str {
+        let file = self.file(file_id);
+        let start = file.line_starts[line_number - 1];
+        let end = file
+            .line_starts
+            .get(line_number)
+            .map_or(file.source.len(), |next| next - 1);
+        file.source[start..end].trim_end_matches('\r')
+    }
+
+    fn file(&self, file_id: FileId) -> &SourceFile {
+        &self.files[file_id.0 as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn should_find_lines_and_columns() {
+        let mut source_map = super::SourceMap::new();
+        let file_id = source_map.add("main.spiral", "One = 1\n\nTwo = 2\n");
+        assert_eq!(source_map.line_number(file_id, 0), 1);
+        assert_eq!(source_map.line_number(file_id, 7), 1);
+        assert_eq!(source_map.line_number(file_id, 8), 2);
+        assert_eq!(source_map.line_number(file_id, 15), 3);
+        assert_eq!(source_map.column(file_id, 15), 6);
+        assert_eq!(source_map.line(file_id, 2), "");
+        assert_eq!(source_map.line(file_id, 3), "Two = 2");
+        assert_eq!(source_map.line(file_id, 4), "");
+    }
+
+    #[test]
+    fn columns_should_count_characters() {
+        let mut source_map = super::SourceMap::new();
+        let file_id = source_map.add("main.spiral", "\"é\" ++ x");
+        assert_eq!(source_map.column(file_id, 8), 7);
+    }
+
+    #[test]
+    fn should_keep_files_apart() {
+        let mut source_map = super::SourceMap::new();
+        let first = source_map.add("one.spiral", "One = 1");
+        let second = source_map.add("two.spiral", "Two =\n  2");
+        assert_eq!(source_map.name(first), "one.spiral");
+        assert_eq!(source_map.source(second), "Two =\n  2");
+        assert_eq!(source_map.line_number(second, 8), 2);
+    }
+}
@@ -0,0 +1,20 @@
+type FileId = super::source_map::FileId;
+
+// A half-open range of byte offsets into one source file. AST nodes and
+// tokens keep a span rather than a copy of the line, and the `SourceMap`
+// turns it into lines and columns when it has to be shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub file_id: FileId,
+    pub start: u32,
+    pub end: u32,
+}
+
+impl Span {
+    pub fn to(self, other: Span) -> Span {
+        Span {
+            end: other.end,
+            ..self
+        }
+    }
+}
@@ -2,50 +2,118 @@ use colored::Colorize;
 use std::error;
 use std::fmt;
 
+type SourceMap = super::source_map::SourceMap;
+type Span = super::span::Span;
+
 pub struct SpiralError<'a> {
     pub error_text: &'a str,
     pub help_text: &'a str,
-    pub line_text: String,
-    pub line_number: usize,
-    pub begin: usize,
-    pub end: usize,
+    pub span: Span,
 }
 
 impl<'a> fmt::Display for SpiralError<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.fmt_for_display())
+        write!(f, "{}", self.error_text)
     }
 }
 
 impl<'a> fmt::Debug for SpiralError<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.fmt_for_display())
+        write!(
+            f,
+            "{} at {}..{}",
+            self.error_text, self.span.start, self.span.end
+        )
     }
 }
 
 impl<'a> SpiralError<'a> {
-    fn fmt_for_display(&self) -> String {
+    // Shows every line the span touches, with carets under the part of each
+    // line that it covers.
+    pub fn render(&self, source_map: &SourceMap) -> String {
+        let file_id = self.span.file_id;
+        let start = self.span.start as usize;
+        let end = (self.span.end as usize).max(start + 1);
+        let first_line = source_map.line_number(file_id, start);
+        let last_line = source_map.line_number(file_id, end - 1);
+        let number_width = last_line.to_string().len();
+
+        let mut lines = String::new();
+        for line_number in first_line..=last_line {
+            let line_text = source_map.line(file_id, line_number);
+            let from = if line_number == first_line {
+                source_map.column(file_id, start)
+            } else {
+                0
+            };
+            let to = if line_number == last_line {
+                source_map.column(file_id, end)
+            } else {
+                line_text.chars().count()
+            };
+
+            lines += &format!(
+                "L{:<width$}: {}\n{}{}\n",
+                line_number,
+                line_text,
+                " ".repeat(from + number_width + 3),
+                "^".repeat(to.saturating_sub(from).max(1)).red(),
+                width = number_width
+            );
+        }
+
         format!(
-            "{}\n\nL{}: {}\n{}\n{}",
+            "{}\n{}:{}:{}\n\n{}{}",
             self.error_text.yellow(),
-            self.line_number,
-            self.line_text,
-            self.error_display().red(),
+            source_map.name(file_id),
+            first_line,
+            source_map.column(file_id, start) + 1,
+            lines,
             self.help_text.green()
         )
     }
+}
 
-    fn error_display(&self) -> String {
-        format!(
-            "{}{}",
-            " ".repeat(self.begin + 3 + self.length_of_line_number()),
-            "^".repeat(self.end - self.begin + 1)
-        )
+impl<'a> error::Error for SpiralError<'a> {}
+
+#[cfg(test)]
+mod tests {
+    type SourceMap = super::super::source_map::SourceMap;
+    type Span = super::super::span::Span;
+
+    #[test]
+    fn should_place_the_caret_under_the_error_on_later_lines() {
+        colored::control::set_override(false);
+        let mut source_map = SourceMap::new();
+        let file_id = source_map.add("main.spiral", "One = 1\nTwo = $");
+        let error = super::SpiralError {
+            error_text: "Unable to parse character",
+            help_text: "",
+            span: Span {
+                file_id,
+                start: 14,
+                end: 15,
+            },
+        };
+        let expected = "Unable to parse character\nmain.spiral:2:7\n\nL2: Two = $\n          ^\n";
+        assert_eq!(error.render(&source_map), expected);
     }
 
-    fn length_of_line_number(&self) -> usize {
-        format!("{}", self.line_number).len()
+    #[test]
+    fn should_underline_every_line_of_a_multi_line_span() {
+        colored::control::set_override(false);
+        let mut source_map = SourceMap::new();
+        let file_id = source_map.add("main.spiral", "Main = (1 +\n  2\n");
+        let error = super::SpiralError {
+            error_text: "Unclosed bracket",
+            help_text: "Please close the bracket",
+            span: Span {
+                file_id,
+                start: 7,
+                end: 15,
+            },
+        };
+        let expected = "Unclosed bracket\nmain.spiral:1:8\n\nL1: Main = (1 +\n           ^^^^\nL2:   2\n    ^^^\nPlease close the bracket";
+        assert_eq!(error.render(&source_map), expected);
     }
 }
-
-impl<'a> error::Error for SpiralError<'a> {}
@@ -1,16 +1,23 @@
-use std::error;
+use std::iter::Peekable;
+use std::str::CharIndices;
+use unicode_xid::UnicodeXID;
 mod and_token_generator;
 mod char_token_generator;
+mod dash_token_generator;
 mod equals_token_generator;
+mod escape_sequence;
 mod function_id_token_generator;
 mod greater_than_token_generator;
+mod left_curly_bracket_token_generator;
 mod less_than_token_generator;
 mod namespace_id_token_generator;
 mod newline_token_generator;
 mod not_token_generator;
 mod number_token_generator;
 mod or_token_generator;
+mod period_token_generator;
 mod plus_token_generator;
+mod right_curly_bracket_token_generator;
 mod simple_token_generator;
 mod string_token_generator;
 mod token_generator;
@@ -18,13 +25,27 @@ mod type_id_token_generator;
 mod whitespace_token_generator;
 mod word_token_generator;
 
-type Result<T> = std::result::Result<T, Box<dyn error::Error>>;
+type FileId = super::source_map::FileId;
+type Result<T> = std::result::Result<T, SpiralError<'static>>;
+type Span = super::span::Span;
 type SpiralError<'a> = super::spiral_error::SpiralError<'a>;
 
+// Scans the input once, front to back. Positions are byte offsets into
+// `input`; lines and columns are left to the `SourceMap`.
 pub struct Tokenizer<'a> {
     pub input: &'a str,
-    pub current_index: usize,
-    pub line_number: usize,
+    file_id: FileId,
+    chars: Peekable<CharIndices<'a>>,
+    token_begin: usize,
+    interpolations: Vec<Interpolation>,
+}
+
+// An interpolation in a string, which is closed by the first '}' that is
+// not matched by a '{' inside it.
+struct Interpolation {
+    start: usize,
+    open_braces: usize,
+    quotes: string_token_generator::Quotes,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -49,6 +70,9 @@ pub enum TokenType {
     Float,
     Char,
     String,
+    StringStart,
+    StringMiddle,
+    StringEnd,
     LeftSquareBracket,
     RightSquareBracket,
     LeftBracket,
@@ -76,156 +100,282 @@ pub enum TokenType {
     Star,
     Caret,
     Period,
+    DoublePeriod,
     Whitespace,
     Newline,
+    Comment,
+    DocComment,
     LeftArrow,
+    RightArrow,
+    Backslash,
+    BlockOpen,
+    BlockSeparator,
+    BlockClose,
 }
 
 #[derive(Debug, Clone)]
 pub struct Token {
     pub value: String,
     pub token_type: TokenType,
-    pub begin: usize,
-    pub end: usize,
-    pub line_number: usize,
-    pub line: String,
+    pub span: Span,
 }
 
+// Tried in order against the first character of each token.
+const TOKEN_GENERATORS: &[&dyn token_generator::TokenGenerator] = &[
+    &type_id_token_generator::TypeIdTokenGenerator {},
+    &namespace_id_token_generator::NamespaceIdTokenGenerator {},
+    &char_token_generator::CharTokenGenerator {},
+    &string_token_generator::StringTokenGenerator {},
+    &simple_token_generator::SimpleTokenGenerator {
+        char_to_match: '[',
+        token_type: TokenType::LeftSquareBracket,
+    },
+    &simple_token_generator::SimpleTokenGenerator {
+        char_to_match: ']',
+        token_type: TokenType::RightSquareBracket,
+    },
+    &simple_token_generator::SimpleTokenGenerator {
+        char_to_match: '(',
+        token_type: TokenType::LeftBracket,
+    },
+    &simple_token_generator::SimpleTokenGenerator {
+        char_to_match: ')',
+        token_type: TokenType::RightBracket,
+    },
+    &left_curly_bracket_token_generator::LeftCurlyBracketTokenGenerator {},
+    &right_curly_bracket_token_generator::RightCurlyBracketTokenGenerator {},
+    &less_than_token_generator::LessThanTokenGenerator {},
+    &greater_than_token_generator::GreaterThanTokenGenerator {},
+    &simple_token_generator::SimpleTokenGenerator {
+        char_to_match: '_',
+        token_type: TokenType::Underscore,
+    },
+    &simple_token_generator::SimpleTokenGenerator {
+        char_to_match: ',',
+        token_type: TokenType::Comma,
+    },
+    &simple_token_generator::SimpleTokenGenerator {
+        char_to_match: ':',
+        token_type: TokenType::Colon,
+    },
+    &or_token_generator::OrTokenGenerator {},
+    &and_token_generator::AndTokenGenerator {},
+    &equals_token_generator::EqualsTokenGenerator {},
+    &not_token_generator::NotTokenGenerator {},
+    &plus_token_generator::PlusTokenGenerator {},
+    &dash_token_generator::DashTokenGenerator {},
+    &simple_token_generator::SimpleTokenGenerator {
+        char_to_match: '/',
+        token_type: TokenType::ForwardSlash,
+    },
+    &simple_token_generator::SimpleTokenGenerator {
+        char_to_match: '*',
+        token_type: TokenType::Star,
+    },
+    &simple_token_generator::SimpleTokenGenerator {
+        char_to_match: '^',
+        token_type: TokenType::Caret,
+    },
+    &period_token_generator::PeriodTokenGenerator {},
+    &simple_token_generator::SimpleTokenGenerator {
+        char_to_match: '\\',
+        token_type: TokenType::Backslash,
+    },
+    &whitespace_token_generator::WhitespaceTokenGenerator {},
+    &newline_token_generator::NewlineTokenGenerator {},
+    &function_id_token_generator::FunctionIdTokenGenerator {},
+    &word_token_generator::WordTokenGenerator {},
+    &number_token_generator::NumberTokenGenerator {},
+];
+
 impl<'a> Tokenizer<'a> {
-    pub fn build(input: &'a str) -> Tokenizer<'a> {
+    pub fn build(file_id: FileId, input: &'a str) -> Tokenizer<'a> {
         Tokenizer {
             input,
-            current_index: 0,
-            line_number: 1,
+            file_id,
+            chars: input.char_indices().peekable(),
+            token_begin: 0,
+            interpolations: Vec::new(),
         }
     }
 
     pub fn execute(&mut self) -> Result<Vec<Token>> {
         let mut tokens = Vec::new();
-        while self.input.len() > self.current_index {
-            let char = self.current_char();
-            if char.is_none() {
-                break;
-            }
-            let unwrapped_char = char.unwrap();
-            let mut matched = false;
-
-            for generator in self.token_generators().iter_mut() {
-                if generator.matches(unwrapped_char) {
-                    tokens.push(generator.generate(unwrapped_char, self)?);
-                    matched = true;
-                    break;
+        while let Some(char) = self.current_char() {
+            self.token_begin = self.offset();
+
+            let generator = TOKEN_GENERATORS
+                .iter()
+                .find(|generator| generator.matches(char));
+            match generator {
+                Some(generator) => {
+                    self.advance();
+                    let token = generator.generate(char, self)?;
+                    if token.token_type != TokenType::Comment {
+                        tokens.push(token);
+                    }
                 }
+                None => return Err(self.error("Unable to parse character", "")),
             }
+        }
+
+        if let Some(interpolation) = self.interpolations.last() {
+            return Err(SpiralError {
+                error_text: "Interpolation must be closed",
+                help_text: "Close the interpolation with '}', e.g. \"Hello {name}\"",
+                span: Span {
+                    file_id: self.file_id,
+                    start: interpolation.start as u32,
+                    end: interpolation.start as u32 + 1,
+                },
+            });
+        }
 
-            if !matched {
-                return Err(Box::new(super::spiral_error::SpiralError {
-                    error_text: "Unable to parse character",
-                    help_text: "",
-                    line_text: self.current_line(),
-                    begin: self.current_index,
-                    end: self.current_index,
-                    line_number: self.line_number,
-                }));
+        Ok(tokens)
+    }
+
+    pub fn current_char(&mut self) -> Option<char> {
+        self.chars.peek().map(|(_, char)| *char)
+    }
+
+    pub fn advance(&mut self) {
+        self.chars.next();
+    }
+
+    pub fn advance_if(&mut self, expected: char) -> bool {
+        if self.current_char() == Some(expected) {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn advance_while(&mut self, predicate: fn(char) -> bool) {
+        while self.current_char().is_some_and(predicate) {
+            self.advance();
+        }
+    }
+
+    // Consumes the '{' that starts an interpolation, remembering how to
+    // carry on with the string once it is closed.
+    pub fn open_interpolation(&mut self, quotes: string_token_generator::Quotes) {
+        let start = self.offset();
+        self.advance();
+        self.interpolations.push(Interpolation {
+            start,
+            open_braces: 0,
+            quotes,
+        });
+    }
+
+    pub fn open_brace(&mut self) {
+        if let Some(interpolation) = self.interpolations.last_mut() {
+            interpolation.open_braces += 1;
+        }
+    }
+
+    // Returns the quotes of the string to carry on with when the '}' just
+    // consumed closes an interpolation rather than a brace opened inside one.
+    pub fn close_brace(&mut self) -> Option<string_token_generator::Quotes> {
+        match self.interpolations.last_mut() {
+            Some(interpolation) if interpolation.open_braces == 0 => self
+                .interpolations
+                .pop()
+                .map(|interpolation| interpolation.quotes),
+            Some(interpolation) => {
+                interpolation.open_braces -= 1;
+                None
             }
-            self.current_index += 1;
+            None => None,
         }
+    }
 
-        return Ok(tokens);
-    }
-
-    fn token_generators(&self) -> Vec<Box<dyn token_generator::TokenGenerator>> {
-        vec![
-            Box::new(type_id_token_generator::TypeIdTokenGenerator {}),
-            Box::new(namespace_id_token_generator::NamespaceIdTokenGenerator {}),
-            Box::new(char_token_generator::CharTokenGenerator {}),
-            Box::new(string_token_generator::StringTokenGenerator {}),
-            Box::new(simple_token_generator::SimpleTokenGenerator {
-                char_to_match: '[',
-                token_type: TokenType::LeftSquareBracket,
-            }),
-            Box::new(simple_token_generator::SimpleTokenGenerator {
-                char_to_match: ']',
-                token_type: TokenType::RightSquareBracket,
-            }),
-            Box::new(simple_token_generator::SimpleTokenGenerator {
-                char_to_match: '(',
-                token_type: TokenType::LeftBracket,
-            }),
-            Box::new(simple_token_generator::SimpleTokenGenerator {
-                char_to_match: ')',
-                token_type: TokenType::RightBracket,
-            }),
-            Box::new(simple_token_generator::SimpleTokenGenerator {
-                char_to_match: '{',
-                token_type: TokenType::LeftCurlyBracket,
-            }),
-            Box::new(simple_token_generator::SimpleTokenGenerator {
-                char_to_match: '}',
-                token_type: TokenType::RightCurlyBracket,
-            }),
-            Box::new(less_than_token_generator::LessThanTokenGenerator {}),
-            Box::new(greater_than_token_generator::GreaterThanTokenGenerator {}),
-            Box::new(simple_token_generator::SimpleTokenGenerator {
-                char_to_match: '_',
-                token_type: TokenType::Underscore,
-            }),
-            Box::new(simple_token_generator::SimpleTokenGenerator {
-                char_to_match: ',',
-                token_type: TokenType::Comma,
-            }),
-            Box::new(simple_token_generator::SimpleTokenGenerator {
-                char_to_match: ':',
-                token_type: TokenType::Colon,
-            }),
-            Box::new(or_token_generator::OrTokenGenerator {}),
-            Box::new(and_token_generator::AndTokenGenerator {}),
-            Box::new(equals_token_generator::EqualsTokenGenerator {}),
-            Box::new(not_token_generator::NotTokenGenerator {}),
-            Box::new(plus_token_generator::PlusTokenGenerator {}),
-            Box::new(simple_token_generator::SimpleTokenGenerator {
-                char_to_match: '-',
-                token_type: TokenType::Dash,
-            }),
-            Box::new(simple_token_generator::SimpleTokenGenerator {
-                char_to_match: '/',
-                token_type: TokenType::ForwardSlash,
-            }),
-            Box::new(simple_token_generator::SimpleTokenGenerator {
-                char_to_match: '*',
-                token_type: TokenType::Star,
-            }),
-            Box::new(simple_token_generator::SimpleTokenGenerator {
-                char_to_match: '^',
-                token_type: TokenType::Caret,
-            }),
-            Box::new(simple_token_generator::SimpleTokenGenerator {
-                char_to_match: '.',
-                token_type: TokenType::Period,
-            }),
-            Box::new(whitespace_token_generator::WhitespaceTokenGenerator {}),
-            Box::new(newline_token_generator::NewlineTokenGenerator {}),
-            Box::new(function_id_token_generator::FunctionIdTokenGenerator {}),
-            Box::new(word_token_generator::WordTokenGenerator {}),
-            Box::new(number_token_generator::NumberTokenGenerator {}),
-        ]
-    }
-
-    pub fn current_char(&self) -> Option<char> {
-        self.input.chars().nth(self.current_index)
-    }
-
-    pub fn current_line(&self) -> String {
-        self.input
-            .lines()
-            .nth(self.line_number - 1)
-            .unwrap()
-            .to_string()
+    // The source text of the token generated so far.
+    pub fn token_text(&mut self) -> &'a str {
+        let end = self.offset();
+        &self.input[self.token_begin..end]
     }
+
+    pub fn token(&mut self, token_type: TokenType) -> Token {
+        let value = self.token_text().to_string();
+        self.token_with_value(token_type, value)
+    }
+
+    pub fn token_with_value(&mut self, token_type: TokenType, value: String) -> Token {
+        let end = self.offset();
+        Token {
+            value,
+            token_type,
+            span: self.span(end),
+        }
+    }
+
+    // Points at the token generated so far, or at its first character if
+    // nothing has been consumed yet.
+    pub fn error(
+        &mut self,
+        error_text: &'static str,
+        help_text: &'static str,
+    ) -> SpiralError<'static> {
+        self.error_from(self.token_begin, error_text, help_text)
+    }
+
+    // Points at part of the token, from `start` to what has been consumed.
+    pub fn error_from(
+        &mut self,
+        start: usize,
+        error_text: &'static str,
+        help_text: &'static str,
+    ) -> SpiralError<'static> {
+        let mut end = self.offset();
+        if end == start {
+            end += self.current_char().map_or(0, char::len_utf8);
+        }
+        SpiralError {
+            error_text,
+            help_text,
+            span: Span {
+                file_id: self.file_id,
+                start: start as u32,
+                end: end as u32,
+            },
+        }
+    }
+
+    pub fn offset(&mut self) -> usize {
+        let length = self.input.len();
+        self.chars.peek().map_or(length, |(index, _)| *index)
+    }
+
+    fn span(&self, end: usize) -> Span {
+        Span {
+            file_id: self.file_id,
+            start: self.token_begin as u32,
+            end: end as u32,
+        }
+    }
+}
+
+// Identifiers follow Unicode's XID rules. Those starting with a capital
+// letter name functions, types and namespaces; any other identifier names a
+// variable or field.
+fn starts_identifier(char: char) -> bool {
+    char.is_xid_start()
+}
+
+fn continues_identifier(char: char) -> bool {
+    char.is_xid_continue()
+}
+
+fn is_capital_letter(char: char) -> bool {
+    char.is_xid_start() && char.is_uppercase()
 }
 
 #[cfg(test)]
 mod tests {
+    type FileId = super::super::source_map::FileId;
+
     #[test]
     fn namespaces_should_parse_with_specific_exposes() {
         let result = parse("namespace @Maths exposing [Function variable #Type]").unwrap();
@@ -793,8 +943,422 @@ mod tests {
         assert_eq!(result.is_err(), true);
     }
 
+    #[test]
+    fn lambdas_should_parse() {
+        let result = parse("\\x y -> x - y").unwrap();
+        let expected = vec![
+            "Backslash",
+            "VariableId",
+            "Whitespace",
+            "VariableId",
+            "Whitespace",
+            "RightArrow",
+            "Whitespace",
+            "VariableId",
+            "Whitespace",
+            "Dash",
+            "Whitespace",
+            "VariableId",
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn dashes_should_not_need_surrounding_whitespace() {
+        let result = parse("a->b-1").unwrap();
+        let expected = vec!["VariableId", "RightArrow", "VariableId", "Dash", "Integer"];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn list_patterns_should_parse_the_rest_of_the_list() {
+        let result = parse("[first ..rest] point.x").unwrap();
+        let expected = vec![
+            "LeftSquareBracket",
+            "VariableId",
+            "Whitespace",
+            "DoublePeriod",
+            "VariableId",
+            "RightSquareBracket",
+            "Whitespace",
+            "VariableId",
+            "Period",
+            "VariableId",
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn should_parse_a_trailing_new_line() {
+        let result = parse("Main = 1\n").unwrap();
+        let expected = vec![
+            "FunctionId",
+            "Whitespace",
+            "Equals",
+            "Whitespace",
+            "Integer",
+            "Newline",
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn tokens_should_record_byte_offsets() {
+        let mut t = super::Tokenizer::build(FileId::default(), "Main =\n\n  Add 12");
+        let tokens = t.execute().unwrap();
+        let positions: Vec<(&str, u32, u32)> = tokens
+            .iter()
+            .map(|token| (token.value.as_str(), token.span.start, token.span.end))
+            .collect();
+        let expected = vec![
+            ("Main", 0, 4),
+            (" ", 4, 5),
+            ("=", 5, 6),
+            ("\n\n", 6, 8),
+            ("  ", 8, 10),
+            ("Add", 10, 13),
+            (" ", 13, 14),
+            ("12", 14, 16),
+        ];
+        assert_eq!(positions, expected);
+    }
+
+    #[test]
+    fn errors_should_cover_the_unknown_character() {
+        let mut t = super::Tokenizer::build(FileId::default(), "One = 1\nTwo = $");
+        let error = t.execute().unwrap_err();
+        assert_eq!((error.span.start, error.span.end), (14, 15));
+    }
+
+    #[test]
+    fn should_skip_line_comments() {
+        let result = parse("Main = 1 -- the answer\n-- another\nx--y").unwrap();
+        let expected = vec![
+            "FunctionId",
+            "Whitespace",
+            "Equals",
+            "Whitespace",
+            "Integer",
+            "Whitespace",
+            "Newline",
+            "Newline",
+            "VariableId",
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn should_skip_nested_block_comments() {
+        let result = parse("{- outer {- inner -} still outer -}x {-\n-}").unwrap();
+        let expected = vec!["VariableId", "Whitespace"];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn should_raise_error_for_unclosed_block_comments() {
+        let result = parse("{- outer {- inner -}");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn should_keep_doc_comments() {
+        let mut t = super::Tokenizer::build(FileId::default(), "--| Adds two\nAddTwo");
+        let tokens = t.execute().unwrap();
+        assert_eq!(tokens[0].token_type, super::TokenType::DocComment);
+        assert_eq!(tokens[0].value, "Adds two");
+        assert_eq!((tokens[0].span.start, tokens[0].span.end), (0, 12));
+    }
+
+    #[test]
+    fn should_decode_escape_sequences() {
+        let input = "\"a\\\\\" '\\n' \"\\t\\\"q\\\" \\u{1F600}\" '\\''";
+        let mut t = super::Tokenizer::build(FileId::default(), input);
+        let values: Vec<String> = t
+            .execute()
+            .unwrap()
+            .into_iter()
+            .filter(|token| token.token_type != super::TokenType::Whitespace)
+            .map(|token| token.value)
+            .collect();
+        assert_eq!(values, vec!["a\\", "\n", "\t\"q\" \u{1F600}", "'"]);
+    }
+
+    #[test]
+    fn should_point_at_unknown_escape_sequences() {
+        let mut t = super::Tokenizer::build(FileId::default(), "\"ab\\qc\"");
+        let error = t.execute().unwrap_err();
+        assert_eq!(error.error_text, "Unknown escape sequence");
+        assert_eq!((error.span.start, error.span.end), (3, 5));
+    }
+
+    #[test]
+    fn should_raise_error_for_invalid_unicode_escapes() {
+        assert_eq!(parse("\"\\u{D800}\"").is_err(), true);
+        assert_eq!(parse("\"\\u{}\"").is_err(), true);
+        assert_eq!(parse("\"\\u1F600\"").is_err(), true);
+    }
+
+    #[test]
+    fn should_raise_error_for_chars_without_exactly_one_character() {
+        assert_eq!(parse("'ab'").is_err(), true);
+        assert_eq!(parse("''").is_err(), true);
+        assert_eq!(parse("'\\u{1F600}'").is_err(), false);
+    }
+
+    #[test]
+    fn should_parse_unicode_identifiers() {
+        let result = parse("größe Überblick wert_2 名前 #Größe @Ünits").unwrap();
+        let expected = vec![
+            "VariableId",
+            "Whitespace",
+            "FunctionId",
+            "Whitespace",
+            "VariableId",
+            "Whitespace",
+            "VariableId",
+            "Whitespace",
+            "TypeId",
+            "Whitespace",
+            "NamespaceId",
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn spans_should_be_byte_offsets_after_non_ascii_text() {
+        let mut t = super::Tokenizer::build(FileId::default(), "\"é\" ++ größe");
+        let tokens = t.execute().unwrap();
+        let last = tokens.last().unwrap();
+        assert_eq!(last.value, "größe");
+        assert_eq!((last.span.start, last.span.end), (8, 15));
+    }
+
+    #[test]
+    fn numbers_should_parse_hex_binary_separators_and_exponents() {
+        let mut t = super::Tokenizer::build(
+            FileId::default(),
+            "0xFF 0b1010 1_000_000 6.02e23 1E-3 2.5e+2",
+        );
+        let tokens: Vec<(String, String)> = t
+            .execute()
+            .unwrap()
+            .into_iter()
+            .filter(|token| token.token_type != super::TokenType::Whitespace)
+            .map(|token| (format!("{:?}", token.token_type), token.value))
+            .collect();
+        let expected = vec![
+            ("Integer", "255"),
+            ("Integer", "10"),
+            ("Integer", "1000000"),
+            ("Float", "6.02e23"),
+            ("Float", "1E-3"),
+            ("Float", "2.5e+2"),
+        ];
+        assert_eq!(
+            tokens,
+            expected
+                .into_iter()
+                .map(|(token_type, value)| (token_type.to_string(), value.to_string()))
+                .collect::<Vec<(String, String)>>()
+        );
+    }
+
+    #[test]
+    fn should_raise_error_for_malformed_numbers() {
+        for input in [
+            "3.", "3.e5", "1e", "2e+", "1_", "1__0", "0x", "0xFG", "0b102", "0x_F", "1e999",
+            "123abc", "1e5x", "2.5f", "7é",
+        ] {
+            assert_eq!(parse(input).is_err(), true, "{}", input);
+        }
+    }
+
+    #[test]
+    fn should_raise_error_for_integers_that_overflow() {
+        assert_eq!(parse("9223372036854775808").is_err(), false);
+        assert_eq!(parse("9223372036854775809").is_err(), true);
+        assert_eq!(parse("0x1_0000_0000_0000_0000").is_err(), true);
+    }
+
+    #[test]
+    fn malformed_numbers_should_point_at_the_whole_literal() {
+        let mut t = super::Tokenizer::build(FileId::default(), "x = 12.");
+        let error = t.execute().unwrap_err();
+        assert_eq!((error.span.start, error.span.end), (4, 7));
+    }
+
+    #[test]
+    fn strings_should_parse_interpolations() {
+        let mut t = super::Tokenizer::build(
+            FileId::default(),
+            "\"Hello {name}, you are {age} years old\"",
+        );
+        let tokens: Vec<(String, String)> = t
+            .execute()
+            .unwrap()
+            .into_iter()
+            .map(|token| (format!("{:?}", token.token_type), token.value))
+            .collect();
+        let expected = vec![
+            ("StringStart", "Hello "),
+            ("VariableId", "name"),
+            ("StringMiddle", ", you are "),
+            ("VariableId", "age"),
+            ("StringEnd", " years old"),
+        ];
+        assert_eq!(
+            tokens,
+            expected
+                .into_iter()
+                .map(|(token_type, value)| (token_type.to_string(), value.to_string()))
+                .collect::<Vec<(String, String)>>()
+        );
+    }
+
+    #[test]
+    fn interpolations_should_allow_braces_and_strings_inside_them() {
+        let result = parse("\"{ { x: 1 }.x } {F \"{y}\"}\"").unwrap();
+        let expected = vec![
+            "StringStart",
+            "Whitespace",
+            "LeftCurlyBracket",
+            "Whitespace",
+            "FieldId",
+            "Whitespace",
+            "Integer",
+            "Whitespace",
+            "RightCurlyBracket",
+            "Period",
+            "VariableId",
+            "Whitespace",
+            "StringMiddle",
+            "FunctionId",
+            "Whitespace",
+            "StringStart",
+            "VariableId",
+            "StringEnd",
+            "StringEnd",
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn escaped_braces_should_not_start_interpolations() {
+        let mut t = super::Tokenizer::build(FileId::default(), "\"\\{name\\}\"");
+        let tokens = t.execute().unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].value, "{name}");
+    }
+
+    #[test]
+    fn should_raise_error_for_unclosed_interpolations() {
+        let mut t = super::Tokenizer::build(FileId::default(), "\"Hello {name");
+        let error = t.execute().unwrap_err();
+        assert_eq!((error.span.start, error.span.end), (7, 8));
+        assert_eq!(parse("\"Hello {name\"").is_err(), true);
+    }
+
+    #[test]
+    fn triple_quoted_strings_should_strip_indentation() {
+        let result = values("Query =\n  \"\"\"\n    SELECT *\n\n      FROM users\n    \"\"\"");
+        assert_eq!(result, vec!["Query", "SELECT *\n\n  FROM users"]);
+    }
+
+    #[test]
+    fn triple_quoted_strings_should_allow_quotes_escapes_and_interpolation() {
+        let result = values("\"\"\"\n  say \"hi\"\\t{name}\n  \"\"\"");
+        assert_eq!(result, vec!["say \"hi\"\t", "name", ""]);
+    }
+
+    #[test]
+    fn raw_strings_should_not_decode_escapes_or_interpolate() {
+        let result = values("r\"C:\\path\\{x}\" r\"\"\"\n  \\d+ {\n  \"\"\" r\"\" r");
+        assert_eq!(result, vec!["C:\\path\\{x}", "\\d+ {", "", "r"]);
+    }
+
+    #[test]
+    fn should_raise_error_for_unclosed_triple_quoted_strings() {
+        assert_eq!(parse("\"\"\"\n  text\n  \"\"").is_err(), true);
+    }
+
+    #[test]
+    fn should_raise_error_at_the_opening_quote_for_strings_left_open() {
+        let mut t = super::Tokenizer::build(FileId::default(), "Main = \"abc\nNext = \"x\"");
+        let error = t.execute().unwrap_err();
+        assert_eq!(error.error_text, "String must be closed on the same line");
+        assert_eq!(error.span.start, 7);
+
+        let mut t = super::Tokenizer::build(FileId::default(), "A = \"{x} y\n\"");
+        let error = t.execute().unwrap_err();
+        assert_eq!(error.span.start, 4);
+    }
+
+    #[test]
+    fn triple_quoted_strings_should_strip_tab_indentation() {
+        let result = values("\"\"\"\n\tone\n\t\ttwo\n\t\"\"\"");
+        assert_eq!(result, vec!["one\n\ttwo"]);
+    }
+
+    #[test]
+    fn should_raise_error_for_mixed_indentation_in_triple_quoted_strings() {
+        assert_eq!(parse("\"\"\"\n\tone\n  two\n\t\"\"\"").is_err(), true);
+    }
+
+    #[test]
+    fn lines_should_stay_accurate_after_multi_line_strings() {
+        let input = "A = \"\"\"\n  x\n  \"\"\"\nB = \"\"\"\n  one\n  two\n  \"\"\" C";
+        let mut t = super::Tokenizer::build(FileId::default(), input);
+        let tokens = t.execute().unwrap();
+
+        let mut source_map = super::super::source_map::SourceMap::new();
+        let file_id = source_map.add("main.spiral", input);
+        let lines: Vec<(&str, usize)> = tokens
+            .iter()
+            .filter(|token| {
+                matches!(
+                    token.token_type,
+                    super::TokenType::FunctionId | super::TokenType::String
+                )
+            })
+            .map(|token| {
+                (
+                    token.value.as_str(),
+                    source_map.line_number(file_id, token.span.start as usize),
+                )
+            })
+            .collect();
+        assert_eq!(
+            lines,
+            vec![("A", 1), ("x", 1), ("B", 4), ("one\ntwo", 4), ("C", 7)]
+        );
+    }
+
+    #[test]
+    fn should_scan_large_inputs() {
+        let input = "Add x y = x + y\n".repeat(20000);
+        let result = parse(&input).unwrap();
+        assert_eq!(result.len(), 20000 * 14);
+    }
+
+    fn values(input: &str) -> Vec<String> {
+        let mut t = super::Tokenizer::build(FileId::default(), input);
+        t.execute()
+            .unwrap()
+            .into_iter()
+            .filter(|token| {
+                !matches!(
+                    token.token_type,
+                    super::TokenType::Whitespace
+                        | super::TokenType::Newline
+                        | super::TokenType::Equals
+                )
+            })
+            .map(|token| token.value)
+            .collect()
+    }
+
     fn parse(input: &str) -> super::Result<Vec<String>> {
-        let mut t = super::Tokenizer::build(input);
+        let mut t = super::Tokenizer::build(FileId::default(), input);
         let tokens = t.execute()?;
         let mut output = Vec::new();
         for token in tokens {
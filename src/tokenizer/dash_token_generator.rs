@@ -0,0 +1,31 @@
+pub struct DashTokenGenerator {}
+
+impl super::token_generator::TokenGenerator for DashTokenGenerator {
+    fn matches(&self, input: char) -> bool {
+        '-' == input
+    }
+
+    fn generate(
+        &self,
+        _input: char,
+        tokenizer: &mut super::Tokenizer,
+    ) -> super::Result<super::Token> {
+        if tokenizer.advance_if('>') {
+            Ok(tokenizer.token(super::TokenType::RightArrow))
+        } else if tokenizer.advance_if('-') {
+            let is_documentation = tokenizer.advance_if('|');
+            tokenizer.advance_while(|char| !matches!(char, '\n' | '\r'));
+            if !is_documentation {
+                return Ok(tokenizer.token(super::TokenType::Comment));
+            }
+
+            // Doc comments keep their text without the '--|' marker and
+            // the space that usually follows it.
+            let text = &tokenizer.token_text()[3..];
+            let value = text.strip_prefix(' ').unwrap_or(text).trim_end();
+            Ok(tokenizer.token_with_value(super::TokenType::DocComment, value.to_string()))
+        } else {
+            Ok(tokenizer.token(super::TokenType::Dash))
+        }
+    }
+}
@@ -0,0 +1,64 @@
+// Decodes the escape sequence following a '\' that has just been consumed.
+// Errors cover the whole sequence, starting at the '\'.
+pub fn decode(tokenizer: &mut super::Tokenizer) -> super::Result<char> {
+    let start = tokenizer.offset() - 1;
+    let char = match tokenizer.current_char() {
+        Some(char) => char,
+        None => {
+            return Err(tokenizer.error_from(
+                start,
+                "Unfinished escape sequence",
+                "Escape sequences look like: \\n, \\t, \\\\, \\\", \\', \\{ or \\u{1F600}",
+            ))
+        }
+    };
+    tokenizer.advance();
+
+    match char {
+        'n' => Ok('\n'),
+        't' => Ok('\t'),
+        '\\' => Ok('\\'),
+        '"' => Ok('"'),
+        '\'' => Ok('\''),
+        '{' => Ok('{'),
+        '}' => Ok('}'),
+        'u' => unicode(tokenizer, start),
+        _ => Err(tokenizer.error_from(
+            start,
+            "Unknown escape sequence",
+            "Escape sequences look like: \\n, \\t, \\\\, \\\", \\', \\{ or \\u{1F600}",
+        )),
+    }
+}
+
+fn unicode(tokenizer: &mut super::Tokenizer, start: usize) -> super::Result<char> {
+    if !tokenizer.advance_if('{') {
+        return Err(tokenizer.error_from(
+            start,
+            "Unicode escapes must be wrapped in braces",
+            "Unicode escapes look like: \\u{1F600}",
+        ));
+    }
+
+    let digits_start = tokenizer.offset();
+    tokenizer.advance_while(|char| char.is_ascii_hexdigit());
+    let digits = &tokenizer.input[digits_start..tokenizer.offset()];
+    if digits.is_empty() || digits.len() > 6 || !tokenizer.advance_if('}') {
+        return Err(tokenizer.error_from(
+            start,
+            "Invalid unicode escape",
+            "Unicode escapes contain 1 to 6 hexadecimal digits, e.g. \\u{1F600}",
+        ));
+    }
+
+    u32::from_str_radix(digits, 16)
+        .ok()
+        .and_then(char::from_u32)
+        .ok_or_else(|| {
+            tokenizer.error_from(
+                start,
+                "Invalid unicode escape",
+                "Unicode escapes must name a unicode scalar value, e.g. \\u{1F600}",
+            )
+        })
+}
@@ -0,0 +1,46 @@
+pub struct LeftCurlyBracketTokenGenerator {}
+
+impl super::token_generator::TokenGenerator for LeftCurlyBracketTokenGenerator {
+    fn matches(&self, input: char) -> bool {
+        '{' == input
+    }
+
+    fn generate(
+        &self,
+        _input: char,
+        tokenizer: &mut super::Tokenizer,
+    ) -> super::Result<super::Token> {
+        if !tokenizer.advance_if('-') {
+            tokenizer.open_brace();
+            return Ok(tokenizer.token(super::TokenType::LeftCurlyBracket));
+        }
+
+        // Block comments nest, so each '{-' needs its own '-}'.
+        let mut depth = 1;
+        while depth > 0 {
+            match tokenizer.current_char() {
+                None => {
+                    return Err(tokenizer.error(
+                        "Block comment must be closed",
+                        "Close the comment with '-}'",
+                    ))
+                }
+                Some('{') => {
+                    tokenizer.advance();
+                    if tokenizer.advance_if('-') {
+                        depth += 1;
+                    }
+                }
+                Some('-') => {
+                    tokenizer.advance();
+                    if tokenizer.advance_if('}') {
+                        depth -= 1;
+                    }
+                }
+                Some(_) => tokenizer.advance(),
+            }
+        }
+
+        Ok(tokenizer.token(super::TokenType::Comment))
+    }
+}
@@ -5,73 +5,163 @@ impl super::token_generator::TokenGenerator for NumberTokenGenerator {
         input.is_ascii_digit()
     }
 
+    // Integers are checked to fit in 64 bits and given a decimal value, so
+    // `0xFF`, `0b1111_1111` and `255` all produce the value "255". Floats
+    // keep their text without digit separators.
     fn generate(
         &self,
-        _input: char,
+        input: char,
         tokenizer: &mut super::Tokenizer,
     ) -> super::Result<super::Token> {
-        let mut value = tokenizer.current_char().unwrap().to_string();
-        let begin_index = tokenizer.current_index;
-        tokenizer.current_index += 1;
-        let mut period_used = false;
+        let radix = match tokenizer.current_char() {
+            Some('x' | 'X') if input == '0' => 16,
+            Some('b' | 'B') if input == '0' => 2,
+            _ => 10,
+        };
+        if radix != 10 {
+            tokenizer.advance();
+            let mut digits = String::new();
+            Self::digits(tokenizer, radix, &mut digits)?;
+            if digits.is_empty()
+                || tokenizer
+                    .current_char()
+                    .is_some_and(super::continues_identifier)
+            {
+                tokenizer.advance_while(super::continues_identifier);
+                return Err(if radix == 16 {
+                    tokenizer.error(
+                        "Invalid hexadecimal literal",
+                        "Hexadecimal literals look like: 0xFF",
+                    )
+                } else {
+                    tokenizer.error(
+                        "Invalid binary literal",
+                        "Binary literals look like: 0b1010",
+                    )
+                });
+            }
+            return Self::integer(tokenizer, &digits, radix);
+        }
+
+        let mut text = input.to_string();
+        Self::digits(tokenizer, 10, &mut text)?;
+        let mut is_float = false;
 
-        let mut char = tokenizer.current_char();
-        if char.is_none() {
-            return Ok(super::Token {
-                value,
-                token_type: super::TokenType::Integer,
-                begin: begin_index,
-                end: tokenizer.current_index,
-                line_number: tokenizer.line_number,
-                line: tokenizer.current_line(),
-            });
+        if tokenizer.advance_if('.') {
+            if !tokenizer
+                .current_char()
+                .is_some_and(|char| char.is_ascii_digit())
+            {
+                return Err(tokenizer.error(
+                    "Float is missing digits after the period",
+                    "Add a digit after the period, e.g. 3.0",
+                ));
+            }
+            text.push('.');
+            Self::digits(tokenizer, 10, &mut text)?;
+            is_float = true;
+
+            if tokenizer.advance_if('.') {
+                return Err(tokenizer.error(
+                    "Number contains multiple periods",
+                    "Ensure the number has a maximum of one period",
+                ));
+            }
         }
-        let mut unwrapped_char = char.unwrap();
 
-        while unwrapped_char.is_ascii_digit() || unwrapped_char == '.' {
-            if unwrapped_char == '.' {
-                if !period_used {
-                    period_used = true;
-                } else {
-                    return Err(Box::new(super::SpiralError {
-                        error_text: "Number contains multiple periods",
-                        help_text: "Ensure the number has a maximum of one period",
-                        line_text: tokenizer.current_line(),
-                        begin: begin_index,
-                        end: begin_index,
-                        line_number: tokenizer.line_number,
-                    }));
-                }
+        if let Some(exponent @ ('e' | 'E')) = tokenizer.current_char() {
+            tokenizer.advance();
+            text.push(exponent);
+            if let Some(sign @ ('+' | '-')) = tokenizer.current_char() {
+                tokenizer.advance();
+                text.push(sign);
             }
+            if !tokenizer
+                .current_char()
+                .is_some_and(|char| char.is_ascii_digit())
+            {
+                return Err(tokenizer.error(
+                    "Float exponent is missing its digits",
+                    "Exponents look like: 6.02e23",
+                ));
+            }
+            Self::digits(tokenizer, 10, &mut text)?;
+            is_float = true;
+        }
 
-            value += &unwrapped_char.to_string();
-            tokenizer.current_index += 1;
-            char = tokenizer.current_char();
-            if char.is_none() {
-                break;
+        if tokenizer
+            .current_char()
+            .is_some_and(super::continues_identifier)
+        {
+            tokenizer.advance_while(super::continues_identifier);
+            return Err(tokenizer.error(
+                "Invalid number literal",
+                "Put a space between a number and a name that follows it",
+            ));
+        }
+
+        if !is_float {
+            return Self::integer(tokenizer, &text, 10);
+        }
+
+        match text.parse::<f64>() {
+            Ok(value) if value.is_finite() => {
+                Ok(tokenizer.token_with_value(super::TokenType::Float, text))
             }
-            unwrapped_char = char.unwrap();
+            _ => Err(tokenizer.error("Float literal is too large", "Floats must fit in 64 bits")),
         }
+    }
+}
 
-        tokenizer.current_index -= 1;
-        if period_used {
-            Ok(super::Token {
-                value,
-                token_type: super::TokenType::Float,
-                begin: begin_index,
-                end: tokenizer.current_index,
-                line_number: tokenizer.line_number,
-                line: tokenizer.current_line(),
-            })
-        } else {
-            Ok(super::Token {
-                value,
-                token_type: super::TokenType::Integer,
-                begin: begin_index,
-                end: tokenizer.current_index,
-                line_number: tokenizer.line_number,
-                line: tokenizer.current_line(),
-            })
+impl NumberTokenGenerator {
+    // Consumes digits in the given radix onto `text`, skipping any '_'
+    // separators between them.
+    fn digits(
+        tokenizer: &mut super::Tokenizer,
+        radix: u32,
+        text: &mut String,
+    ) -> super::Result<()> {
+        let mut follows_digit = text.ends_with(|char: char| char.is_digit(radix));
+        loop {
+            match tokenizer.current_char() {
+                Some(char) if char.is_digit(radix) => {
+                    tokenizer.advance();
+                    text.push(char);
+                    follows_digit = true;
+                }
+                Some('_') => {
+                    tokenizer.advance();
+                    let precedes_digit = tokenizer
+                        .current_char()
+                        .is_some_and(|char| char.is_digit(radix));
+                    if !follows_digit || !precedes_digit {
+                        return Err(tokenizer.error(
+                            "Digit separators must be between two digits",
+                            "Separate groups of digits with '_', e.g. 1_000_000",
+                        ));
+                    }
+                    follows_digit = false;
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn integer(
+        tokenizer: &mut super::Tokenizer,
+        digits: &str,
+        radix: u32,
+    ) -> super::Result<super::Token> {
+        // One past the largest i64 is let through, as the parser accepts it
+        // after a '-'.
+        match u64::from_str_radix(digits, radix) {
+            Ok(value) if value <= i64::MIN.unsigned_abs() => {
+                Ok(tokenizer.token_with_value(super::TokenType::Integer, value.to_string()))
+            }
+            _ => Err(tokenizer.error(
+                "Integer literal is too large",
+                "Integers must be between -9223372036854775808 and 9223372036854775807",
+            )),
         }
     }
 }
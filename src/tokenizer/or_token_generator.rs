@@ -10,48 +10,12 @@ impl super::token_generator::TokenGenerator for OrTokenGenerator {
         _input: char,
         tokenizer: &mut super::Tokenizer,
     ) -> super::Result<super::Token> {
-        let mut value = tokenizer.current_char().unwrap().to_string();
-        let begin_index = tokenizer.current_index;
-        tokenizer.current_index += 1;
-
-        let char = tokenizer.current_char().ok_or(super::SpiralError {
-            error_text: "Unknown Character: '|'",
-            help_text: "",
-            line_text: tokenizer.current_line(),
-            begin: begin_index,
-            end: begin_index,
-            line_number: tokenizer.line_number,
-        })?;
-
-        if char == '|' {
-            value += &char.to_string();
-            Ok(super::Token {
-                value,
-                token_type: super::TokenType::Or,
-                begin: begin_index,
-                end: tokenizer.current_index,
-                line_number: tokenizer.line_number,
-                line: tokenizer.current_line(),
-            })
-        } else if char == '>' {
-            value += &char.to_string();
-            Ok(super::Token {
-                value,
-                token_type: super::TokenType::Flow,
-                begin: begin_index,
-                end: tokenizer.current_index,
-                line_number: tokenizer.line_number,
-                line: tokenizer.current_line(),
-            })
+        if tokenizer.advance_if('|') {
+            Ok(tokenizer.token(super::TokenType::Or))
+        } else if tokenizer.advance_if('>') {
+            Ok(tokenizer.token(super::TokenType::Flow))
         } else {
-            Err(Box::new(super::SpiralError {
-                error_text: "Unknown Character: '|'",
-                help_text: "",
-                line_text: tokenizer.current_line(),
-                begin: begin_index,
-                end: begin_index,
-                line_number: tokenizer.line_number,
-            }))
+            Err(tokenizer.error("Unknown Character: '|'", ""))
         }
     }
 }
@@ -0,0 +1,19 @@
+pub struct PeriodTokenGenerator {}
+
+impl super::token_generator::TokenGenerator for PeriodTokenGenerator {
+    fn matches(&self, input: char) -> bool {
+        '.' == input
+    }
+
+    fn generate(
+        &self,
+        _input: char,
+        tokenizer: &mut super::Tokenizer,
+    ) -> super::Result<super::Token> {
+        if tokenizer.advance_if('.') {
+            Ok(tokenizer.token(super::TokenType::DoublePeriod))
+        } else {
+            Ok(tokenizer.token(super::TokenType::Period))
+        }
+    }
+}
@@ -0,0 +1,18 @@
+pub struct RightCurlyBracketTokenGenerator {}
+
+impl super::token_generator::TokenGenerator for RightCurlyBracketTokenGenerator {
+    fn matches(&self, input: char) -> bool {
+        '}' == input
+    }
+
+    fn generate(
+        &self,
+        _input: char,
+        tokenizer: &mut super::Tokenizer,
+    ) -> super::Result<super::Token> {
+        match tokenizer.close_brace() {
+            Some(quotes) => super::string_token_generator::segment(tokenizer, quotes, true),
+            None => Ok(tokenizer.token(super::TokenType::RightCurlyBracket)),
+        }
+    }
+}
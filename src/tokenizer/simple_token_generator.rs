@@ -10,16 +10,9 @@ impl super::token_generator::TokenGenerator for SimpleTokenGenerator {
 
     fn generate(
         &self,
-        input: char,
+        _input: char,
         tokenizer: &mut super::Tokenizer,
     ) -> super::Result<super::Token> {
-        Ok(super::Token {
-            value: input.to_string(),
-            token_type: self.token_type.clone(),
-            begin: tokenizer.current_index,
-            end: tokenizer.current_index,
-            line_number: tokenizer.line_number,
-            line: tokenizer.current_line(),
-        })
+        Ok(tokenizer.token(self.token_type.clone()))
     }
 }
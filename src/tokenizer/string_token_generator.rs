@@ -10,54 +10,142 @@ impl super::token_generator::TokenGenerator for StringTokenGenerator {
         _input: char,
         tokenizer: &mut super::Tokenizer,
     ) -> super::Result<super::Token> {
-        let mut value = String::from("");
-        let begin_index = tokenizer.current_index;
-        tokenizer.current_index += 1;
-
-        let mut char = tokenizer.current_char().ok_or(super::SpiralError {
-            error_text: "String must be closed",
-            help_text: "",
-            line_text: tokenizer.current_line(),
-            begin: begin_index,
-            end: begin_index,
-            line_number: tokenizer.line_number,
-        })?;
-
-        while !(char == '"'
-            && tokenizer
-                .input
-                .chars()
-                .nth(tokenizer.current_index - 1)
-                .unwrap()
-                != '\\')
-        {
-            value += &char.to_string();
-            tokenizer.current_index += 1;
-            let char_result = tokenizer.current_char();
-            if char_result.is_none() {
-                break;
+        open(tokenizer, false)
+    }
+}
+
+// How the string being scanned was opened. Only triple quoted strings may
+// span several lines, and raw strings (`r"..."`) take escapes and braces
+// literally. `indentation` is the byte range of the first line's indentation.
+#[derive(Clone, Copy)]
+pub struct Quotes {
+    start: usize,
+    triple: bool,
+    raw: bool,
+    indentation: Option<(usize, usize)>,
+}
+
+// Called with the opening '"' consumed. A triple quoted string starts with
+// `"""`, while `""` is an empty string.
+pub fn open(tokenizer: &mut super::Tokenizer, raw: bool) -> super::Result<super::Token> {
+    let mut quotes = Quotes {
+        start: tokenizer.token_begin,
+        triple: false,
+        raw,
+        indentation: None,
+    };
+    if tokenizer.advance_if('"') {
+        if !tokenizer.advance_if('"') {
+            return Ok(tokenizer.token_with_value(super::TokenType::String, String::new()));
+        }
+        quotes.triple = true;
+
+        // The line break after the opening quotes is not part of the string.
+        tokenizer.advance_if('\r');
+        tokenizer.advance_if('\n');
+    }
+
+    segment(tokenizer, quotes, false)
+}
+
+// Scans string text up to the closing quotes or the '{' of an
+// interpolation. A string without interpolations is a single `String` token;
+// otherwise it is split into `StringStart`, `StringMiddle` and `StringEnd`
+// tokens around the tokens of each interpolated expression. `resumes` is set
+// when the scan carries on after the '}' of an interpolation.
+//
+// Triple quoted strings lose the indentation of their first line from every
+// line, and a last line holding only the indentation of the closing quotes
+// is dropped, so they can be indented along with the code around them. The
+// indentation may use tabs, but every line has to start with the same mix.
+pub fn segment(
+    tokenizer: &mut super::Tokenizer,
+    mut quotes: Quotes,
+    resumes: bool,
+) -> super::Result<super::Token> {
+    let mut value = String::new();
+    let mut at_line_start = quotes.triple && !resumes;
+    loop {
+        if at_line_start {
+            at_line_start = false;
+            let start = tokenizer.offset();
+            tokenizer.advance_while(|char| char == ' ' || char == '\t');
+            let end = tokenizer.offset();
+
+            if closes(tokenizer, quotes) {
+                if value.ends_with('\n') {
+                    value.pop();
+                    if value.ends_with('\r') {
+                        value.pop();
+                    }
+                }
+            } else if !matches!(tokenizer.current_char(), Some('\n' | '\r')) {
+                let (from, to) = *quotes.indentation.get_or_insert((start, end));
+                let input = tokenizer.input;
+                let indentation = &input[from..to];
+                let whitespace = &input[start..end];
+                match whitespace.strip_prefix(indentation) {
+                    Some(rest) => value.push_str(rest),
+                    None if indentation.starts_with(whitespace) => {}
+                    None => {
+                        return Err(tokenizer.error_from(
+                            start,
+                            "Inconsistent indentation in multi-line string",
+                            "Indent every line of the string with the same tabs and spaces as the first",
+                        ))
+                    }
+                }
             }
-            char = char_result.unwrap();
         }
 
-        if char != '"' {
-            Err(Box::new(super::SpiralError {
-                error_text: "String must be closed",
-                help_text: "",
-                line_text: tokenizer.current_line(),
-                begin: begin_index,
-                end: tokenizer.current_index,
-                line_number: tokenizer.line_number,
-            }))
-        } else {
-            Ok(super::Token {
-                value,
-                token_type: super::TokenType::String,
-                begin: begin_index,
-                end: tokenizer.current_index,
-                line_number: tokenizer.line_number,
-                line: tokenizer.current_line(),
-            })
+        match tokenizer.current_char() {
+            None => return Err(tokenizer.error("String must be closed", "")),
+            Some('"') if closes(tokenizer, quotes) => {
+                let length = if quotes.triple { 3 } else { 1 };
+                for _ in 0..length {
+                    tokenizer.advance();
+                }
+                let token_type = if resumes {
+                    super::TokenType::StringEnd
+                } else {
+                    super::TokenType::String
+                };
+                return Ok(tokenizer.token_with_value(token_type, value));
+            }
+            Some('{') if !quotes.raw => {
+                tokenizer.open_interpolation(quotes);
+                let token_type = if resumes {
+                    super::TokenType::StringMiddle
+                } else {
+                    super::TokenType::StringStart
+                };
+                return Ok(tokenizer.token_with_value(token_type, value));
+            }
+            Some('\\') if !quotes.raw => {
+                tokenizer.advance();
+                value.push(super::escape_sequence::decode(tokenizer)?);
+            }
+            Some('\n' | '\r') if !quotes.triple => {
+                return Err(tokenizer.error_from(
+                    quotes.start,
+                    "String must be closed on the same line",
+                    "Close the string with '\"', or use \"\"\" for text over several lines",
+                ));
+            }
+            Some(char) => {
+                tokenizer.advance();
+                value.push(char);
+                at_line_start = quotes.triple && char == '\n';
+            }
         }
     }
 }
+
+fn closes(tokenizer: &mut super::Tokenizer, quotes: Quotes) -> bool {
+    let rest = &tokenizer.input[tokenizer.offset()..];
+    if quotes.triple {
+        rest.starts_with("\"\"\"")
+    } else {
+        rest.starts_with('"')
+    }
+}
@@ -2,7 +2,7 @@ pub struct WordTokenGenerator {}
 
 impl super::token_generator::TokenGenerator for WordTokenGenerator {
     fn matches(&self, input: char) -> bool {
-        input.is_ascii_lowercase()
+        super::starts_identifier(input) && !super::is_capital_letter(input)
     }
 
     fn generate(
@@ -10,156 +10,29 @@ impl super::token_generator::TokenGenerator for WordTokenGenerator {
         _input: char,
         tokenizer: &mut super::Tokenizer,
     ) -> super::Result<super::Token> {
-        let mut value = tokenizer.current_char().unwrap().to_string();
-        let begin_index = tokenizer.current_index;
-        tokenizer.current_index += 1;
-
-        let mut char = tokenizer.current_char();
-        if char.is_none() {
-            return Ok(super::Token {
-                value,
-                token_type: super::TokenType::VariableId,
-                begin: begin_index,
-                end: tokenizer.current_index,
-                line_number: tokenizer.line_number,
-                line: tokenizer.current_line(),
-            });
+        tokenizer.advance_while(super::continues_identifier);
+        if tokenizer.token_text() == "r" && tokenizer.advance_if('"') {
+            return super::string_token_generator::open(tokenizer, true);
         }
-        let mut unwrapped_char = char.unwrap();
-
-        while unwrapped_char.is_ascii_uppercase()
-            || unwrapped_char.is_ascii_lowercase()
-            || unwrapped_char == ':'
-        {
-            value += &unwrapped_char.to_string();
-            if unwrapped_char == ':' {
-                return Ok(super::Token {
-                    value,
-                    token_type: super::TokenType::FieldId,
-                    begin: begin_index,
-                    end: tokenizer.current_index,
-                    line_number: tokenizer.line_number,
-                    line: tokenizer.current_line(),
-                });
-            }
 
-            tokenizer.current_index += 1;
-            char = tokenizer.current_char();
-            if char.is_none() {
-                break;
-            }
-            unwrapped_char = char.unwrap();
+        if tokenizer.advance_if(':') {
+            return Ok(tokenizer.token(super::TokenType::FieldId));
         }
 
-        tokenizer.current_index -= 1;
-        if value == "namespace" {
-            Ok(super::Token {
-                value,
-                token_type: super::TokenType::KeywordNamespace,
-                begin: begin_index,
-                end: tokenizer.current_index,
-                line_number: tokenizer.line_number,
-                line: tokenizer.current_line(),
-            })
-        } else if value == "exposing" {
-            Ok(super::Token {
-                value,
-                token_type: super::TokenType::KeywordExposing,
-                begin: begin_index,
-                end: tokenizer.current_index,
-                line_number: tokenizer.line_number,
-                line: tokenizer.current_line(),
-            })
-        } else if value == "import" {
-            Ok(super::Token {
-                value,
-                token_type: super::TokenType::KeywordImport,
-                begin: begin_index,
-                end: tokenizer.current_index,
-                line_number: tokenizer.line_number,
-                line: tokenizer.current_line(),
-            })
-        } else if value == "let" {
-            Ok(super::Token {
-                value,
-                token_type: super::TokenType::KeywordLet,
-                begin: begin_index,
-                end: tokenizer.current_index,
-                line_number: tokenizer.line_number,
-                line: tokenizer.current_line(),
-            })
-        } else if value == "in" {
-            Ok(super::Token {
-                value,
-                token_type: super::TokenType::KeywordIn,
-                begin: begin_index,
-                end: tokenizer.current_index,
-                line_number: tokenizer.line_number,
-                line: tokenizer.current_line(),
-            })
-        } else if value == "if" {
-            Ok(super::Token {
-                value,
-                token_type: super::TokenType::KeywordIf,
-                begin: begin_index,
-                end: tokenizer.current_index,
-                line_number: tokenizer.line_number,
-                line: tokenizer.current_line(),
-            })
-        } else if value == "else" {
-            Ok(super::Token {
-                value,
-                token_type: super::TokenType::KeywordElse,
-                begin: begin_index,
-                end: tokenizer.current_index,
-                line_number: tokenizer.line_number,
-                line: tokenizer.current_line(),
-            })
-        } else if value == "match" {
-            Ok(super::Token {
-                value,
-                token_type: super::TokenType::KeywordMatch,
-                begin: begin_index,
-                end: tokenizer.current_index,
-                line_number: tokenizer.line_number,
-                line: tokenizer.current_line(),
-            })
-        } else if value == "when" {
-            Ok(super::Token {
-                value,
-                token_type: super::TokenType::KeywordWhen,
-                begin: begin_index,
-                end: tokenizer.current_index,
-                line_number: tokenizer.line_number,
-                line: tokenizer.current_line(),
-            })
-        } else if value == "true" {
-            Ok(super::Token {
-                value,
-                token_type: super::TokenType::KeywordTrue,
-                begin: begin_index,
-                end: tokenizer.current_index,
-                line_number: tokenizer.line_number,
-                line: tokenizer.current_line(),
-            })
-        } else if value == "false" {
-            Ok(super::Token {
-                value,
-                token_type: super::TokenType::KeywordFalse,
-                begin: begin_index,
-                end: tokenizer.current_index,
-                line_number: tokenizer.line_number,
-                line: tokenizer.current_line(),
-            })
-        } else {
-            Ok(super::Token {
-                value,
-                token_type: super::TokenType::VariableId,
-                begin: begin_index,
-                end: tokenizer.current_index,
-                line_number: tokenizer.line_number,
-                line: tokenizer.current_line(),
-            })
-        }
+        let token_type = match tokenizer.token_text() {
+            "namespace" => super::TokenType::KeywordNamespace,
+            "exposing" => super::TokenType::KeywordExposing,
+            "import" => super::TokenType::KeywordImport,
+            "let" => super::TokenType::KeywordLet,
+            "in" => super::TokenType::KeywordIn,
+            "if" => super::TokenType::KeywordIf,
+            "else" => super::TokenType::KeywordElse,
+            "match" => super::TokenType::KeywordMatch,
+            "when" => super::TokenType::KeywordWhen,
+            "true" => super::TokenType::KeywordTrue,
+            "false" => super::TokenType::KeywordFalse,
+            _ => super::TokenType::VariableId,
+        };
+        Ok(tokenizer.token(token_type))
     }
 }